@@ -0,0 +1,203 @@
+//! Atomic saves, rotating backups and undo
+//!
+//! Usage:
+//! ```bash
+//! # Any modifying command keeps the previous version of the UFO
+//! lilufo --ufo-path font.ufo round-to-even
+//!
+//! # Restore it
+//! lilufo --ufo-path font.ufo undo
+//! ```
+//!
+//! Changes are written to a temporary sibling of the UFO, which then takes
+//! the UFO's place, so a failed or interrupted write never leaves a
+//! half-written font behind. The replaced version is moved into
+//! `.lilufo/backups/<ufo name>/` next to the UFO; the newest
+//! [`MAX_BACKUPS`] are kept.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{anyhow, Result};
+
+/// Directory, next to the UFO, that holds backups
+pub const BACKUP_DIR: &str = ".lilufo/backups";
+
+/// Number of backups kept per UFO
+pub const MAX_BACKUPS: usize = 10;
+
+fn ufo_name(ufo_path: &Path) -> Result<&std::ffi::OsStr> {
+    ufo_path.file_name().ok_or_else(|| anyhow!("Invalid UFO path: {}", ufo_path.display()))
+}
+
+fn parent_dir(ufo_path: &Path) -> &Path {
+    ufo_path.parent().unwrap_or(Path::new(""))
+}
+
+/// A sibling of the UFO used while it is being replaced
+fn sibling(ufo_path: &Path, suffix: &str) -> Result<PathBuf> {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(ufo_name(ufo_path)?);
+    name.push(format!(".lilufo-{}", suffix));
+    Ok(parent_dir(ufo_path).join(name))
+}
+
+/// Directory holding the backups of one UFO
+pub fn backup_dir(ufo_path: &Path) -> Result<PathBuf> {
+    Ok(parent_dir(ufo_path).join(BACKUP_DIR).join(ufo_name(ufo_path)?))
+}
+
+/// Lists the backups of a UFO, oldest first
+pub fn backups(ufo_path: &Path) -> Result<Vec<PathBuf>> {
+    let dir = backup_dir(ufo_path)?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut backups = fs::read_dir(&dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<PathBuf>>>()?;
+    // Names are zero-padded timestamps, so they sort chronologically
+    backups.sort();
+    Ok(backups)
+}
+
+/// Replaces the UFO at `ufo_path` with the one `write` creates at the path
+/// it is given, keeping the current version as a backup
+pub fn replace_ufo(ufo_path: &Path, write: impl FnOnce(&Path) -> Result<()>) -> Result<()> {
+    let staging = sibling(ufo_path, "staging")?;
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    if let Err(e) = write(&staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let dir = backup_dir(ufo_path)?;
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+    let backup = dir.join(format!("{:020}", timestamp));
+
+    fs::rename(ufo_path, &backup)?;
+    if let Err(e) = fs::rename(&staging, ufo_path) {
+        fs::rename(&backup, ufo_path)?;
+        return Err(e.into());
+    }
+
+    let backups = backups(ufo_path)?;
+    for old in &backups[..backups.len().saturating_sub(MAX_BACKUPS)] {
+        fs::remove_dir_all(old)?;
+    }
+    Ok(())
+}
+
+/// Copies a UFO (or any directory) recursively
+pub fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Restores the most recent backup of a UFO, discarding the current
+/// version. Returns the backup that was restored.
+pub fn undo(ufo_path: &Path) -> Result<PathBuf> {
+    let backup = backups(ufo_path)?
+        .pop()
+        .ok_or_else(|| anyhow!("No backups of {} to restore", ufo_path.display()))?;
+
+    let discarded = sibling(ufo_path, "discarded")?;
+    if discarded.exists() {
+        fs::remove_dir_all(&discarded)?;
+    }
+    if ufo_path.exists() {
+        fs::rename(ufo_path, &discarded)?;
+    }
+    if let Err(e) = fs::rename(&backup, ufo_path) {
+        if discarded.exists() {
+            fs::rename(&discarded, ufo_path)?;
+        }
+        return Err(e.into());
+    }
+    if discarded.exists() {
+        fs::remove_dir_all(&discarded)?;
+    }
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory holding a fake UFO with a single file
+    fn scratch_ufo(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lilufo-backup-{}-{}", test, std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        let ufo = dir.join("font.ufo");
+        fs::create_dir_all(&ufo).unwrap();
+        fs::write(ufo.join("version.txt"), "0").unwrap();
+        ufo
+    }
+
+    fn version(ufo: &Path) -> String {
+        fs::read_to_string(ufo.join("version.txt")).unwrap()
+    }
+
+    fn write_version(ufo: &Path, version: usize) -> Result<()> {
+        replace_ufo(ufo, |staging| {
+            fs::create_dir_all(staging)?;
+            Ok(fs::write(staging.join("version.txt"), version.to_string())?)
+        })
+    }
+
+    #[test]
+    fn keeps_the_newest_backups() {
+        let ufo = scratch_ufo("rotation");
+        for version in 1..=MAX_BACKUPS + 2 {
+            write_version(&ufo, version).unwrap();
+        }
+        assert_eq!(version(&ufo), (MAX_BACKUPS + 2).to_string());
+        let kept: Vec<String> = backups(&ufo).unwrap().iter().map(|backup| version(backup)).collect();
+        let expected: Vec<String> = (2..MAX_BACKUPS + 2).map(|version| version.to_string()).collect();
+        assert_eq!(kept, expected);
+        fs::remove_dir_all(ufo.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn a_failed_write_leaves_the_ufo_alone() {
+        let ufo = scratch_ufo("failure");
+        assert!(replace_ufo(&ufo, |staging| {
+            fs::create_dir_all(staging)?;
+            Err(anyhow!("Interrupted"))
+        })
+        .is_err());
+        assert_eq!(version(&ufo), "0");
+        assert!(backups(&ufo).unwrap().is_empty());
+        assert!(!sibling(&ufo, "staging").unwrap().exists());
+        fs::remove_dir_all(ufo.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn undo_restores_the_previous_versions_in_turn() {
+        let ufo = scratch_ufo("undo");
+        write_version(&ufo, 1).unwrap();
+        write_version(&ufo, 2).unwrap();
+
+        undo(&ufo).unwrap();
+        assert_eq!(version(&ufo), "1");
+        undo(&ufo).unwrap();
+        assert_eq!(version(&ufo), "0");
+        assert!(undo(&ufo).is_err());
+        assert_eq!(version(&ufo), "0");
+        fs::remove_dir_all(ufo.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+//! Running a command on many UFOs at once
+//!
+//! Usage:
+//! ```bash
+//! # Every master in a directory (quote the glob or repeat --ufo-path)
+//! lilufo --ufo-path 'masters/*.ufo' round-to-even
+//!
+//! # Every source of a designspace
+//! lilufo --designspace MyFamily.designspace add-kerning-group --name O --side left --members O,Q
+//! ```
+//!
+//! Each font is processed independently and in parallel; a failure in one
+//! does not stop the others. Files a command writes with `--output` get the
+//! UFO's name added (`kern.csv` becomes `kern.Bold.csv`), so fonts never
+//! overwrite each other's output.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Result};
+use glob::glob;
+use crate::designspace;
+
+/// Expands `--ufo-path` values and designspaces into the UFOs to process, in
+/// the order given and without duplicates. A path that exists is taken
+/// as-is; anything else is treated as a glob pattern that must match.
+pub fn expand_targets(ufo_paths: &[String], designspaces: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut targets = Vec::new();
+    for pattern in ufo_paths {
+        if Path::new(pattern).exists() {
+            targets.push(PathBuf::from(pattern));
+            continue;
+        }
+        let matches = glob(pattern)?.collect::<Result<Vec<PathBuf>, _>>()?;
+        if matches.is_empty() {
+            return Err(anyhow!("No UFO found at {}", pattern));
+        }
+        targets.extend(matches);
+    }
+
+    for path in designspaces {
+        let document = designspace::load_designspace(path)?;
+        let problems = designspace::validate_designspace(path, &document);
+        if !problems.is_empty() {
+            return Err(anyhow!("Invalid designspace {}:\n  {}", path.display(), problems.join("\n  ")));
+        }
+        targets.extend(designspace::source_paths(path, &document));
+    }
+
+    let mut seen = HashSet::new();
+    targets.retain(|path| seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())));
+    Ok(targets)
+}
+
+/// The file a batch writes one font's `--output` to: the UFO's name is
+/// added before the extension
+pub fn output_path(output: &Path, ufo_path: &Path) -> PathBuf {
+    let ufo_name = ufo_path.file_stem().unwrap_or(ufo_path.as_os_str());
+    let mut name = output.file_stem().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(ufo_name);
+    if let Some(extension) = output.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    output.with_file_name(name)
+}
+
+/// Fails if two UFOs would write their output to the same file
+pub fn check_output_paths(output: &Path, targets: &[PathBuf]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for ufo_path in targets {
+        let path = output_path(output, ufo_path);
+        if !seen.insert(path.clone()) {
+            return Err(anyhow!(
+                "Several UFOs would write {}; give them distinct names or run them separately",
+                path.display()
+            ));
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,14 @@
-use std::path::PathBuf;
 use anyhow::Result;
+use norad::Font;
+use lilufo::GroupSide;
+use lilufo::kerning::MemberPolicy;
 
 /// Executes the AddKerningGroup command
-pub fn execute(ufo_path: &PathBuf, name: &str, side: &str, members: &[String]) -> Result<()> {
-    crate::kerning::add_kerning_group(ufo_path, name, side, members)
-} 
\ No newline at end of file
+pub fn execute(font: &mut Font, name: &str, side: GroupSide, members: &[String], policy: MemberPolicy) -> Result<()> {
+    let moved = lilufo::kerning::add_kerning_group(font, name, side, members, policy)?;
+    for member in &moved {
+        println!("Moved '{}' from @{}", member.glyph, member.from_group);
+    }
+    println!("Successfully added kerning group '{}'", name);
+    Ok(())
+}
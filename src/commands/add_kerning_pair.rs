@@ -1,7 +1,9 @@
-use std::path::PathBuf;
 use anyhow::Result;
+use norad::Font;
 
 /// Executes the AddKerningPair command
-pub fn execute(ufo_path: &PathBuf, first: &str, second: &str, value: i32) -> Result<()> {
-    crate::kerning::add_kerning_pair(ufo_path, first, second, value)
-} 
\ No newline at end of file
+pub fn execute(font: &mut Font, first: &str, second: &str, value: f64, allow_missing: bool) -> Result<()> {
+    lilufo::kerning::add_kerning_pair(font, first, second, value, allow_missing)?;
+    println!("Successfully added kerning pair '{}' '{}' → {}", first, second, value);
+    Ok(())
+}
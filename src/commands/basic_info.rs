@@ -1,6 +1,47 @@
 use norad::Font;
+use anyhow::Result;
+use super::OutputFormat;
 
 /// Executes the BasicInfo command
-pub fn execute(font: &Font) {
-    crate::basic::display_basic_info(font);
-} 
\ No newline at end of file
+pub fn execute(font: &Font, format: OutputFormat) -> Result<()> {
+    let summary = lilufo::font_summary(font);
+
+    match format {
+        OutputFormat::Text => {
+            super::print_banner();
+            println!("Lil' UFO");
+            println!();
+            println!("Font Information:");
+            println!("Family Name: {}", summary.family_name.as_deref().unwrap_or("N/A"));
+            println!("Style Name: {}", summary.style_name.as_deref().unwrap_or("N/A"));
+            println!("Version Major: {}", summary.version_major.unwrap_or(0));
+            println!("Version Minor: {}", summary.version_minor.unwrap_or(0));
+            println!("Number of Glyphs: {}", summary.glyph_count);
+        }
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "family_name": summary.family_name,
+                "style_name": summary.style_name,
+                "version_major": summary.version_major,
+                "version_minor": summary.version_minor,
+                "glyph_count": summary.glyph_count,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            let rows = [
+                ["key".to_string(), "value".to_string()],
+                ["family_name".to_string(), summary.family_name.clone().unwrap_or_default()],
+                ["style_name".to_string(), summary.style_name.clone().unwrap_or_default()],
+                ["version_major".to_string(), summary.version_major.map(|v| v.to_string()).unwrap_or_default()],
+                ["version_minor".to_string(), summary.version_minor.map(|v| v.to_string()).unwrap_or_default()],
+                ["glyph_count".to_string(), summary.glyph_count.to_string()],
+            ];
+            for row in rows {
+                println!("{}", super::record(format, &row));
+            }
+        }
+        OutputFormat::Matrix => format.ensure_not_matrix("basic-info")?,
+    }
+    Ok(())
+}
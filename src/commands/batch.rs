@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+use std::process::Command;
+use anyhow::Result;
+use rayon::prelude::*;
+
+/// Runs the current command on each UFO in a separate process, in parallel,
+/// then prints every font's output followed by a summary
+pub fn execute(targets: &[PathBuf]) -> Result<()> {
+    let executable = std::env::current_exe()?;
+    let args: Vec<_> = std::env::args_os().skip(1).collect();
+
+    let outputs: Vec<_> = targets
+        .par_iter()
+        .map(|ufo_path| Command::new(&executable).arg("--batch-font").arg(ufo_path).args(&args).output())
+        .collect();
+
+    let mut failures = Vec::new();
+    for (ufo_path, output) in targets.iter().zip(&outputs) {
+        println!("== {} ==", ufo_path.display());
+        match output {
+            Ok(output) => {
+                print!("{}", String::from_utf8_lossy(&output.stdout));
+                eprint!("{}", String::from_utf8_lossy(&output.stderr));
+                if !output.status.success() {
+                    // The error the font's run ended with, without its causes
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let error = stderr.lines().rev().find_map(|line| line.strip_prefix("Error: "));
+                    failures.push((ufo_path, error.unwrap_or("failed").to_string()));
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                failures.push((ufo_path, e.to_string()));
+            }
+        }
+        println!();
+    }
+
+    println!("Summary:");
+    for ufo_path in targets {
+        match failures.iter().find(|(failed, _)| *failed == ufo_path) {
+            Some((_, error)) => println!("  failed  {}: {}", ufo_path.display(), error),
+            None => println!("  ok      {}", ufo_path.display()),
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(anyhow::anyhow!("{} of {} font(s) failed", failures.len(), targets.len()));
+    }
+    println!("All {} font(s) succeeded", targets.len());
+    Ok(())
+}
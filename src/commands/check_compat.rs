@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+use anyhow::Result;
+use norad::Font;
+use super::OutputFormat;
+
+/// Executes the CheckCompat command
+pub fn execute(masters: &[PathBuf], format: OutputFormat) -> Result<()> {
+    let fonts = masters
+        .iter()
+        .map(|path| Font::load(path).map_err(|e| anyhow::anyhow!("Failed to load {}: {}", path.display(), e)))
+        .collect::<Result<Vec<Font>>>()?;
+    let names: Vec<String> = masters.iter().map(|path| path.display().to_string()).collect();
+    let incompatibilities = lilufo::compat::check_compatibility(&fonts.iter().collect::<Vec<_>>());
+
+    match format {
+        OutputFormat::Text => {
+            if incompatibilities.is_empty() {
+                println!("All {} masters are compatible", masters.len());
+            }
+            for incompatibility in &incompatibilities {
+                let values: Vec<String> = names
+                    .iter()
+                    .zip(&incompatibility.values)
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect();
+                let location = incompatibility.location.as_ref().map(|l| format!(" {}", l)).unwrap_or_default();
+                println!("{}{}: {} ({})", incompatibility.subject, location, incompatibility.kind, values.join("; "));
+            }
+        }
+        OutputFormat::Json => {
+            let json: Vec<_> = incompatibilities
+                .iter()
+                .map(|incompatibility| {
+                    serde_json::json!({
+                        "kind": incompatibility.kind.to_string(),
+                        "subject": incompatibility.subject,
+                        "location": incompatibility.location,
+                        "values": names.iter().zip(&incompatibility.values).map(|(name, value)| {
+                            serde_json::json!({ "master": name, "value": value })
+                        }).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "incompatibilities": json }))?);
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            let header: Vec<&str> = ["kind", "subject", "location"].into_iter().chain(names.iter().map(String::as_str)).collect();
+            println!("{}", super::record(format, &header));
+            for incompatibility in &incompatibilities {
+                let fields: Vec<String> = [
+                    incompatibility.kind.to_string(),
+                    incompatibility.subject.clone(),
+                    incompatibility.location.clone().unwrap_or_default(),
+                ]
+                .into_iter()
+                .chain(incompatibility.values.iter().cloned())
+                .collect();
+                println!("{}", super::record(format, &fields));
+            }
+        }
+        OutputFormat::Matrix => format.ensure_not_matrix("check-compat")?,
+    }
+
+    if !incompatibilities.is_empty() {
+        return Err(anyhow::anyhow!("Found {} difference(s) between masters", incompatibilities.len()));
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+use anyhow::Result;
+use super::OutputFormat;
+
+/// Executes the CheckContents command
+pub fn execute(ufo_path: &Path, format: OutputFormat) -> Result<()> {
+    let reports = lilufo::layers::check_contents(ufo_path)?;
+    let problems: usize = reports.iter().map(|report| report.orphans.len() + report.missing.len()).sum();
+
+    match format {
+        OutputFormat::Text => {
+            if problems == 0 {
+                println!("All glyph files match contents.plist");
+            }
+            for report in reports.iter().filter(|report| !report.is_clean()) {
+                println!("{}:", report.layer);
+                for orphan in &report.orphans {
+                    println!("  orphan {} (not in contents.plist)", orphan.display());
+                }
+                for glyph in &report.missing {
+                    println!("  missing {} (listed for '{}')", glyph.path.display(), glyph.name);
+                }
+            }
+        }
+        OutputFormat::Json => {
+            let json: Vec<_> = reports
+                .iter()
+                .map(|report| {
+                    serde_json::json!({
+                        "layer": report.layer,
+                        "orphans": report.orphans.iter().map(|path| path.display().to_string()).collect::<Vec<_>>(),
+                        "missing": report
+                            .missing
+                            .iter()
+                            .map(|glyph| serde_json::json!({ "glyph": glyph.name, "path": glyph.path.display().to_string() }))
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "layers": json }))?);
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            println!("{}", super::record(format, &["layer", "problem", "glyph", "path"]));
+            for report in &reports {
+                for orphan in &report.orphans {
+                    println!("{}", super::record(format, &[report.layer.as_str(), "orphan", "", &orphan.display().to_string()]));
+                }
+                for glyph in &report.missing {
+                    println!(
+                        "{}",
+                        super::record(format, &[report.layer.as_str(), "missing", &glyph.name, &glyph.path.display().to_string()])
+                    );
+                }
+            }
+        }
+        OutputFormat::Matrix => format.ensure_not_matrix("check-contents")?,
+    }
+
+    if problems > 0 {
+        return Err(anyhow::anyhow!("Found {} contents.plist problem(s)", problems));
+    }
+    Ok(())
+}
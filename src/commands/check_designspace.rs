@@ -0,0 +1,40 @@
+use std::path::Path;
+use anyhow::Result;
+use super::OutputFormat;
+
+/// Executes the CheckDesignspace command
+pub fn execute(designspace: &Path, format: OutputFormat) -> Result<()> {
+    let document = lilufo::designspace::load_designspace(designspace)?;
+    let problems = lilufo::designspace::validate_designspace(designspace, &document);
+
+    match format {
+        OutputFormat::Text => {
+            if problems.is_empty() {
+                println!(
+                    "Designspace is valid: {} axis/axes, {} source(s), {} instance(s)",
+                    document.axes.len(),
+                    document.sources.len(),
+                    document.instances.len()
+                );
+            }
+            for problem in &problems {
+                println!("{}", problem);
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "problems": problems }))?);
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            println!("{}", super::record(format, &["problem"]));
+            for problem in &problems {
+                println!("{}", super::record(format, &[problem]));
+            }
+        }
+        OutputFormat::Matrix => format.ensure_not_matrix("check-designspace")?,
+    }
+
+    if !problems.is_empty() {
+        return Err(anyhow::anyhow!("Found {} problem(s) in {}", problems.len(), designspace.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+use anyhow::Result;
+use lilufo::grid::{ElementSelection, Grid, OffGridCoordinate};
+use lilufo::layers::LayerSelection;
+use super::OutputFormat;
+
+/// Executes the CheckGrid command
+pub fn execute(
+    ufo_path: &Path,
+    layers: &LayerSelection,
+    elements: &ElementSelection,
+    grid: &Grid,
+    format: OutputFormat
+) -> Result<()> {
+    let off_grid = lilufo::grid::check_grid(ufo_path, layers, elements, grid)?;
+    let contour = |coordinate: &OffGridCoordinate| coordinate.contour.map(|c| c.to_string()).unwrap_or_default();
+
+    match format {
+        OutputFormat::Text => {
+            if off_grid.is_empty() {
+                println!("All coordinates are on {}", grid);
+            }
+            for coordinate in &off_grid {
+                let location = match coordinate.contour {
+                    Some(contour) => format!("contour {} point {}", contour, coordinate.index),
+                    None => format!("{} {}", coordinate.kind.element_name(), coordinate.index),
+                };
+                println!(
+                    "{} ({}): {} {} = {}",
+                    coordinate.glyph, coordinate.layer, location, coordinate.attribute, coordinate.value
+                );
+            }
+        }
+        OutputFormat::Json => {
+            let json: Vec<_> = off_grid
+                .iter()
+                .map(|coordinate| {
+                    serde_json::json!({
+                        "layer": coordinate.layer,
+                        "glyph": coordinate.glyph,
+                        "element": coordinate.kind.element_name(),
+                        "contour": coordinate.contour,
+                        "index": coordinate.index,
+                        "attribute": coordinate.attribute,
+                        "value": coordinate.value,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "off_grid": json }))?);
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            println!("{}", super::record(format, &["layer", "glyph", "element", "contour", "index", "attribute", "value"]));
+            for coordinate in &off_grid {
+                println!(
+                    "{}",
+                    super::record(
+                        format,
+                        &[
+                            coordinate.layer.clone(),
+                            coordinate.glyph.clone(),
+                            coordinate.kind.element_name().to_string(),
+                            contour(coordinate),
+                            coordinate.index.to_string(),
+                            coordinate.attribute.to_string(),
+                            coordinate.value.clone(),
+                        ]
+                    )
+                );
+            }
+        }
+        OutputFormat::Matrix => format.ensure_not_matrix("check-grid")?,
+    }
+
+    if !off_grid.is_empty() {
+        return Err(anyhow::anyhow!("Found {} coordinate(s) off {}", off_grid.len(), grid));
+    }
+    Ok(())
+}
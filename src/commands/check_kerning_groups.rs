@@ -0,0 +1,48 @@
+use std::path::Path;
+use anyhow::Result;
+use super::OutputFormat;
+
+/// Executes the CheckKerningGroups command
+pub fn execute(ufo_path: &Path, format: OutputFormat) -> Result<()> {
+    // norad refuses to load fonts with conflicting groups, so read the plist directly
+    let groups = lilufo::kerning::load_groups(ufo_path)?;
+    let conflicts = lilufo::kerning::find_group_conflicts(&groups);
+
+    match format {
+        OutputFormat::Text => {
+            if conflicts.is_empty() {
+                println!("No kerning group conflicts found");
+            }
+            for conflict in &conflicts {
+                let groups: Vec<String> = conflict.groups.iter().map(|g| format!("@{}", g)).collect();
+                println!("{} ({}): {}", conflict.glyph, conflict.side, groups.join(", "));
+            }
+        }
+        OutputFormat::Json => {
+            let json: Vec<_> = conflicts
+                .iter()
+                .map(|conflict| {
+                    serde_json::json!({
+                        "glyph": conflict.glyph,
+                        "side": conflict.side.to_string(),
+                        "groups": conflict.groups.iter().map(|g| conflict.side.full_name(g)).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "conflicts": json }))?);
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            println!("{}", super::record(format, &["glyph", "side", "groups"]));
+            for conflict in &conflicts {
+                let groups: Vec<String> = conflict.groups.iter().map(|g| conflict.side.full_name(g)).collect();
+                println!("{}", super::record(format, &[conflict.glyph.clone(), conflict.side.to_string(), groups.join(",")]));
+            }
+        }
+        OutputFormat::Matrix => format.ensure_not_matrix("check-kerning-groups")?,
+    }
+
+    if !conflicts.is_empty() {
+        return Err(anyhow::anyhow!("Found {} kerning group conflict(s)", conflicts.len()));
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use std::path::Path;
+use anyhow::Result;
+use lilufo::designspace::{AxisSpec, SourceSpec};
+
+/// Executes the CreateDesignspace command
+pub fn execute(out: &Path, axes: &[AxisSpec], sources: &[SourceSpec], dry_run: bool) -> Result<()> {
+    let document = lilufo::designspace::create_designspace(out, axes, sources, dry_run)?;
+
+    println!(
+        "{} {} with {} axis/axes and {} source(s)",
+        if dry_run { "Dry run: would write" } else { "Wrote" },
+        out.display(),
+        document.axes.len(),
+        document.sources.len()
+    );
+    Ok(())
+}
@@ -1,7 +1,21 @@
-use std::path::PathBuf;
 use anyhow::Result;
+use norad::Font;
+use lilufo::GroupSide;
+use lilufo::kerning::MemberPolicy;
 
 /// Executes the EditKerningGroup command
-pub fn execute(ufo_path: &PathBuf, name: &str, side: &str, members: &[String], append: bool) -> Result<()> {
-    crate::kerning::edit_kerning_group(ufo_path, name, side, members, append)
-} 
\ No newline at end of file
+pub fn execute(
+    font: &mut Font,
+    name: &str,
+    side: GroupSide,
+    members: &[String],
+    append: bool,
+    policy: MemberPolicy
+) -> Result<()> {
+    let moved = lilufo::kerning::edit_kerning_group(font, name, side, members, append, policy)?;
+    for member in &moved {
+        println!("Moved '{}' from @{}", member.glyph, member.from_group);
+    }
+    println!("Successfully updated kerning group '{}'", name);
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::fs;
+use std::path::Path;
+use anyhow::Result;
+use norad::Font;
+use lilufo::fea;
+
+/// Executes the ExportFea command
+pub fn execute(font: &mut Font, output: Option<&Path>, merge: bool, dry_run: bool) -> Result<()> {
+    let block = fea::export_fea(font);
+
+    if let Some(output) = output {
+        if dry_run {
+            println!("Dry run: would write kern feature to {}", output.display());
+        } else {
+            fs::write(output, &block)?;
+            println!("Wrote kern feature to {}", output.display());
+        }
+    }
+
+    if merge {
+        font.features = fea::merge_into_features(&font.features, &block);
+        println!("Merged kern feature into features.fea");
+    }
+
+    if output.is_none() && !merge {
+        print!("{}", block);
+    }
+    Ok(())
+}
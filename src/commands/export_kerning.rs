@@ -0,0 +1,36 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use anyhow::Result;
+use norad::Font;
+use lilufo::kerning_io;
+use super::OutputFormat;
+
+/// Executes the ExportKerning command
+pub fn execute(font: &Font, output: Option<&Path>, format: OutputFormat, dry_run: bool) -> Result<()> {
+    let table = lilufo::kerning::kerning_table(font);
+
+    let Some(output) = output else {
+        return super::show_kerning::write_pairs(&mut std::io::stdout().lock(), &table, format);
+    };
+    if dry_run {
+        println!("Dry run: would write {} kerning pair(s) to {}", table.len(), output.display());
+        return Ok(());
+    }
+
+    // Files are meant for spreadsheets, so text and matrices are written
+    // as CSV, or as TSV for .tsv files
+    let is_tsv = output.extension().is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+    let delimiter = if is_tsv { OutputFormat::Tsv } else { OutputFormat::Csv };
+
+    let mut file = BufWriter::new(File::create(output)?);
+    match format {
+        OutputFormat::Matrix => {
+            super::show_kerning::write_matrix(&mut file, &kerning_io::kerning_matrix(&table), Some(delimiter))?
+        }
+        OutputFormat::Text => super::show_kerning::write_pairs(&mut file, &table, delimiter)?,
+        _ => super::show_kerning::write_pairs(&mut file, &table, format)?,
+    }
+    println!("Wrote {} kerning pair(s) to {}", table.len(), output.display());
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use anyhow::Result;
+use norad::Font;
+use super::OutputFormat;
+
+/// Executes the FlattenKerning command
+pub fn execute(font: &mut Font, write: bool, output: Option<&Path>, format: OutputFormat, dry_run: bool) -> Result<()> {
+    let table = lilufo::kerning::flatten_kerning(font);
+
+    if let Some(output) = output {
+        if dry_run {
+            println!("Dry run: would write {} glyph pair(s) to {}", table.len(), output.display());
+        } else {
+            let mut file = BufWriter::new(File::create(output)?);
+            super::show_kerning::write_pairs(&mut file, &table, format)?;
+            println!("Wrote {} glyph pair(s) to {}", table.len(), output.display());
+        }
+    }
+
+    if write {
+        lilufo::kerning::replace_kerning(font, &table)?;
+        println!("Replaced kerning with {} glyph pair(s)", table.len());
+    }
+
+    if output.is_none() && !write {
+        super::show_kerning::write_pairs(&mut std::io::stdout().lock(), &table, format)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use std::path::Path;
+use anyhow::Result;
+
+/// Executes the GenerateInstances command
+pub fn execute(designspace: &Path, dry_run: bool) -> Result<()> {
+    let instances = lilufo::designspace::generate_instances(designspace, dry_run)?;
+
+    for instance in &instances {
+        println!("{} → {}", instance.name, instance.path.display());
+    }
+    if dry_run {
+        println!("Dry run: {} instance(s) were not written", instances.len());
+    } else {
+        println!("Generated {} instance(s)", instances.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::path::Path;
+use anyhow::Result;
+use norad::Font;
+use lilufo::kerning::display_name;
+use lilufo::kerning_io::{self, MergeStrategy};
+
+/// Executes the ImportKerning command
+pub fn execute(font: &mut Font, from: &Path, strategy: MergeStrategy, allow_missing: bool) -> Result<()> {
+    let table = kerning_io::read_kerning_source(from)?;
+    let summary = kerning_io::import_kerning(font, &table, strategy, allow_missing)?;
+
+    for pair in &summary.inserted {
+        println!("inserted {} {} → {}", pair.first_display(), pair.second_display(), pair.value);
+    }
+    for change in &summary.changed {
+        println!(
+            "changed {} {}: {} → {}",
+            display_name(&change.first),
+            display_name(&change.second),
+            change.old_value,
+            change.new_value
+        );
+    }
+    for skipped in &summary.skipped {
+        println!(
+            "skipped {} {}: {}",
+            skipped.pair.first_display(),
+            skipped.pair.second_display(),
+            skipped.reason
+        );
+    }
+    println!(
+        "Imported from {}: {} inserted, {} changed, {} skipped",
+        from.display(),
+        summary.inserted.len(),
+        summary.changed.len(),
+        summary.skipped.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use norad::Font;
+
+/// Executes the Interpolate command
+pub fn execute(masters: &[PathBuf], factor: f64, out: &Path, dry_run: bool) -> Result<()> {
+    let fonts = masters
+        .iter()
+        .map(|path| Font::load(path).map_err(|e| anyhow::anyhow!("Failed to load {}: {}", path.display(), e)))
+        .collect::<Result<Vec<Font>>>()?;
+    let instance = lilufo::interpolate::interpolate_masters(&fonts, factor)?;
+
+    if dry_run {
+        println!("Dry run: {} was not written", out.display());
+    } else if out.exists() {
+        lilufo::backup::replace_ufo(out, |staging| Ok(instance.save(staging)?))?;
+    } else {
+        instance.save(out)?;
+    }
+
+    println!(
+        "Interpolated {} glyph(s) and {} kerning pair(s) at factor {}",
+        instance.default_layer().len(),
+        lilufo::kerning::kerning_table(&instance).len(),
+        factor
+    );
+    if !dry_run {
+        println!("Wrote {}", out.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use norad::Font;
+use anyhow::Result;
+use lilufo::kerning::display_name;
+use super::OutputFormat;
+
+/// Executes the KernLookup command
+pub fn execute(font: &Font, first: &str, second: &str, format: OutputFormat) -> Result<()> {
+    let resolution = lilufo::kerning::lookup_kerning(font, first, second);
+
+    match format {
+        OutputFormat::Text => {
+            println!("{} {} → {}", first, second, resolution.value());
+            println!("First group: {}", resolution.first_group.as_deref().map(display_name).unwrap_or_else(|| "none".to_string()));
+            println!("Second group: {}", resolution.second_group.as_deref().map(display_name).unwrap_or_else(|| "none".to_string()));
+
+            match resolution.winner() {
+                Some((source, pair)) => {
+                    let kind = if resolution.is_exception() { "exception" } else { "entry" };
+                    println!(
+                        "From {} {}: {} {} → {}",
+                        source, kind, pair.first_display(), pair.second_display(), pair.value
+                    );
+                    for (source, pair) in &resolution.matches[1..] {
+                        println!(
+                            "  overrides {}: {} {} → {}",
+                            source, pair.first_display(), pair.second_display(), pair.value
+                        );
+                    }
+                }
+                None => println!("No kerning entry applies to this pair"),
+            }
+        }
+        OutputFormat::Json => {
+            let matches: Vec<_> = resolution
+                .matches
+                .iter()
+                .map(|(source, pair)| {
+                    serde_json::json!({
+                        "source": source.to_string(),
+                        "first": pair.first,
+                        "second": pair.second,
+                        "value": pair.value,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "first": resolution.first,
+                "second": resolution.second,
+                "value": resolution.value(),
+                "first_group": resolution.first_group,
+                "second_group": resolution.second_group,
+                "exception": resolution.is_exception(),
+                "matches": matches,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            println!("{}", super::record(format, &["source", "first", "second", "value"]));
+            for (source, pair) in &resolution.matches {
+                let row = [source.to_string(), pair.first.clone(), pair.second.clone(), pair.value.to_string()];
+                println!("{}", super::record(format, &row));
+            }
+        }
+        OutputFormat::Matrix => format.ensure_not_matrix("kern-lookup")?,
+    }
+    Ok(())
+}
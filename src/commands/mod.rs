@@ -6,6 +6,29 @@ pub mod show_kerning;
 pub mod add_kerning_group;
 pub mod edit_kerning_group;
 pub mod add_kerning_pair;
+pub mod remove_kerning_pair;
+pub mod remove_kerning_group;
+pub mod round_kerning;
+pub mod check_kerning_groups;
+pub mod flatten_kerning;
+pub mod kern_lookup;
+pub mod export_fea;
+pub mod import_kerning;
+pub mod export_kerning;
+pub mod snap_to_grid;
+pub mod check_contents;
+pub mod check_grid;
+pub mod undo;
+pub mod check_compat;
+pub mod interpolate;
+pub mod check_designspace;
+pub mod generate_instances;
+pub mod create_designspace;
+pub mod batch;
+pub mod sync_groups;
+
+use lilufo::diff::{FontDiff, LineChange};
+use lilufo::kerning::display_name;
 
 // Re-export command implementations for convenience
 pub use basic_info::execute as execute_basic_info;
@@ -14,4 +37,122 @@ pub use show_kerning_groups::execute as execute_show_kerning_groups;
 pub use show_kerning::execute as execute_show_kerning;
 pub use add_kerning_group::execute as execute_add_kerning_group;
 pub use edit_kerning_group::execute as execute_edit_kerning_group;
-pub use add_kerning_pair::execute as execute_add_kerning_pair; 
\ No newline at end of file
+pub use add_kerning_pair::execute as execute_add_kerning_pair;
+pub use remove_kerning_pair::execute as execute_remove_kerning_pair;
+pub use remove_kerning_group::execute as execute_remove_kerning_group;
+pub use round_kerning::execute as execute_round_kerning;
+pub use check_kerning_groups::execute as execute_check_kerning_groups;
+pub use flatten_kerning::execute as execute_flatten_kerning;
+pub use kern_lookup::execute as execute_kern_lookup;
+pub use export_fea::execute as execute_export_fea;
+pub use import_kerning::execute as execute_import_kerning;
+pub use export_kerning::execute as execute_export_kerning;
+pub use snap_to_grid::execute as execute_snap_to_grid;
+pub use check_contents::execute as execute_check_contents;
+pub use check_grid::execute as execute_check_grid;
+pub use undo::execute as execute_undo;
+pub use check_compat::execute as execute_check_compat;
+pub use interpolate::execute as execute_interpolate;
+pub use check_designspace::execute as execute_check_designspace;
+pub use generate_instances::execute as execute_generate_instances;
+pub use create_designspace::execute as execute_create_designspace;
+pub use batch::execute as execute_batch;
+pub use sync_groups::execute as execute_sync_groups;
+
+/// Output format for the read-only commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    Text,
+    /// A single JSON document
+    Json,
+    /// Tab-separated values with a header row
+    Tsv,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Kerning as a table of left sides (rows) by right sides (columns)
+    Matrix,
+}
+
+impl OutputFormat {
+    /// Fails for formats that only make sense for kerning tables
+    pub fn ensure_not_matrix(self, command: &str) -> anyhow::Result<()> {
+        if self == OutputFormat::Matrix {
+            return Err(anyhow::anyhow!("--format matrix is not supported by {}", command));
+        }
+        Ok(())
+    }
+}
+
+/// Formats one row of a TSV or CSV table, quoting CSV fields where needed
+pub fn record<S: AsRef<str>>(format: OutputFormat, fields: &[S]) -> String {
+    if format == OutputFormat::Csv {
+        fields
+            .iter()
+            .map(|field| {
+                let field = field.as_ref();
+                if field.contains([',', '"', '\n']) {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    } else {
+        fields.iter().map(|field| field.as_ref()).collect::<Vec<_>>().join("\t")
+    }
+}
+
+/// Prints what a command changed in a font, one line per change
+pub fn print_font_diff(diff: &FontDiff) {
+    for pair in &diff.added_pairs {
+        println!("+ kerning {} {} {}", pair.first_display(), pair.second_display(), pair.value);
+    }
+    for pair in &diff.removed_pairs {
+        println!("- kerning {} {} {}", pair.first_display(), pair.second_display(), pair.value);
+    }
+    for change in &diff.changed_pairs {
+        println!(
+            "~ kerning {} {}: {} → {}",
+            display_name(&change.first),
+            display_name(&change.second),
+            change.old_value,
+            change.new_value
+        );
+    }
+    for group in &diff.groups {
+        match (&group.before, &group.after) {
+            (None, Some(members)) => println!("+ group {}: {}", group.name, members.join(", ")),
+            (Some(_), None) => println!("- group {}", group.name),
+            _ => {
+                let added = group.added_members().into_iter().map(|m| format!("+{}", m));
+                let removed = group.removed_members().into_iter().map(|m| format!("-{}", m));
+                let changes: Vec<String> = added.chain(removed).collect();
+                if changes.is_empty() {
+                    println!("~ group {}: members reordered", group.name);
+                } else {
+                    println!("~ group {}: {}", group.name, changes.join(" "));
+                }
+            }
+        }
+    }
+    if !diff.features.is_empty() {
+        println!("~ features.fea");
+        for line in &diff.features {
+            match line {
+                LineChange::Removed(line) => println!("  -{}", line),
+                LineChange::Added(line) => println!("  +{}", line),
+            }
+        }
+    }
+}
+
+/// Prints the Lil' UFO ASCII art banner
+pub fn print_banner() {
+    println!("    .     *     .           .     ");
+    println!("   .-----.                        ");
+    println!(" _/___@_@_\\_              .      ");
+    println!("(___________)      *              ");
+    println!("                                  ");
+}
@@ -0,0 +1,22 @@
+use anyhow::Result;
+use norad::Font;
+use lilufo::GroupSide;
+use lilufo::kerning::ReferencePolicy;
+
+/// Executes the RemoveKerningGroup command
+pub fn execute(
+    font: &mut Font,
+    name: &str,
+    side: GroupSide,
+    references: Option<ReferencePolicy>
+) -> Result<()> {
+    let removal = lilufo::kerning::remove_kerning_group(font, name, side, references)?;
+    println!("Successfully removed kerning group '{}' ({})", name, removal.members.join(", "));
+    for pair in &removal.removed_pairs {
+        println!("  removed {} {} → {}", pair.first_display(), pair.second_display(), pair.value);
+    }
+    for pair in &removal.added_pairs {
+        println!("  added {} {} → {}", pair.first_display(), pair.second_display(), pair.value);
+    }
+    Ok(())
+}
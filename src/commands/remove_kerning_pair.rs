@@ -0,0 +1,9 @@
+use anyhow::Result;
+use norad::Font;
+
+/// Executes the RemoveKerningPair command
+pub fn execute(font: &mut Font, first: &str, second: &str) -> Result<()> {
+    let value = lilufo::kerning::remove_kerning_pair(font, first, second)?;
+    println!("Successfully removed kerning pair '{}' '{}' (was {})", first, second, value);
+    Ok(())
+}
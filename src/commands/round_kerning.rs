@@ -0,0 +1,24 @@
+use anyhow::Result;
+use norad::Font;
+use lilufo::kerning::display_name;
+
+/// Executes the RoundKerning command
+pub fn execute(font: &mut Font) -> Result<()> {
+    let changes = lilufo::kerning::round_kerning(font);
+    if changes.is_empty() {
+        println!("All kerning values are already integers");
+        return Ok(());
+    }
+
+    for change in &changes {
+        println!(
+            "{} {}: {} → {}",
+            display_name(&change.first),
+            display_name(&change.second),
+            change.old_value,
+            change.new_value
+        );
+    }
+    println!("Rounded {} kerning value(s)", changes.len());
+    Ok(())
+}
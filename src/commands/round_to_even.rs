@@ -1,7 +1,16 @@
-use std::path::PathBuf;
+use std::path::Path;
 use anyhow::Result;
+use lilufo::grid::{ElementSelection, Grid};
+use lilufo::layers::LayerSelection;
+use lilufo::topology::Safeguard;
 
-/// Executes the RoundToEven command
-pub fn execute(ufo_path: &PathBuf) -> Result<()> {
-    crate::basic::round_points_to_even(ufo_path)
-} 
\ No newline at end of file
+/// Executes the RoundToEven command, which snaps to the 2-unit grid
+pub fn execute(
+    ufo_path: &Path,
+    layers: &LayerSelection,
+    elements: &ElementSelection,
+    safeguard: Option<Safeguard>,
+    dry_run: bool
+) -> Result<()> {
+    super::execute_snap_to_grid(ufo_path, layers, elements, &Grid::EVEN, safeguard, dry_run)
+}
@@ -1,7 +1,94 @@
-use std::path::PathBuf;
+use std::io::Write;
+use norad::Font;
 use anyhow::Result;
+use lilufo::KerningTable;
+use lilufo::kerning::display_name;
+use lilufo::kerning_io::{self, KerningMatrix};
+use super::OutputFormat;
 
 /// Executes the ShowKerning command
-pub fn execute(ufo_path: &PathBuf) -> Result<()> {
-    crate::kerning::display_kerning(ufo_path)
-} 
\ No newline at end of file
+pub fn execute(font: &Font, format: OutputFormat) -> Result<()> {
+    let table = lilufo::kerning::kerning_table(font);
+    write_pairs(&mut std::io::stdout().lock(), &table, format)
+}
+
+/// Writes a kerning table in the given format, as shown by `show-kerning`
+pub fn write_pairs(out: &mut impl Write, table: &KerningTable, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            if table.is_empty() {
+                writeln!(out, "No kerning pairs found in UFO")?;
+                return Ok(());
+            }
+
+            writeln!(out, "Kerning Pairs:")?;
+            writeln!(out, "--------------")?;
+            for pair in table.iter() {
+                writeln!(out, "{} {} → {}", pair.first_display(), pair.second_display(), pair.value)?;
+            }
+        }
+        OutputFormat::Json => {
+            let json: Vec<_> = table
+                .iter()
+                .map(|pair| {
+                    serde_json::json!({
+                        "first": pair.first,
+                        "second": pair.second,
+                        "value": pair.value,
+                    })
+                })
+                .collect();
+            writeln!(out, "{}", serde_json::to_string_pretty(&serde_json::json!({ "pairs": json }))?)?;
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            writeln!(out, "{}", super::record(format, &["first", "second", "value"]))?;
+            for pair in table.iter() {
+                writeln!(out, "{}", super::record(format, &[pair.first.clone(), pair.second.clone(), pair.value.to_string()]))?;
+            }
+        }
+        OutputFormat::Matrix => write_matrix(out, &kerning_io::kerning_matrix(table), None)?,
+    }
+    Ok(())
+}
+
+/// Writes a kerning matrix, aligned for the terminal or, given a TSV/CSV format,
+/// as a delimited table for spreadsheets
+pub fn write_matrix(out: &mut impl Write, matrix: &KerningMatrix, delimited: Option<OutputFormat>) -> Result<()> {
+    let mut header = vec![String::new()];
+    header.extend(matrix.columns.iter().map(|key| display_name(key)));
+    let mut rows = vec![header];
+    for (row, key) in matrix.rows.iter().enumerate() {
+        let mut cells = vec![display_name(key)];
+        cells.extend((0..matrix.columns.len()).map(|column| {
+            matrix.get(row, column).map(|value| value.to_string()).unwrap_or_default()
+        }));
+        rows.push(cells);
+    }
+
+    if let Some(format) = delimited {
+        for cells in &rows {
+            writeln!(out, "{}", super::record(format, cells))?;
+        }
+        return Ok(());
+    }
+
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|column| rows.iter().map(|cells| cells[column].chars().count()).max().unwrap_or(0))
+        .collect();
+    for cells in &rows {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if column == 0 {
+                    format!("{:<width$}", cell, width = *width)
+                } else {
+                    format!("{:>width$}", cell, width = *width)
+                }
+            })
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
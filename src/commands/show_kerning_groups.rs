@@ -1,7 +1,53 @@
-use std::path::PathBuf;
+use norad::Font;
 use anyhow::Result;
+use lilufo::kerning::{self, GroupSide};
+use super::OutputFormat;
 
 /// Executes the ShowKerningGroups command
-pub fn execute(ufo_path: &PathBuf) -> Result<()> {
-    crate::kerning::display_kerning_groups(ufo_path)
-} 
\ No newline at end of file
+pub fn execute(font: &Font, format: OutputFormat) -> Result<()> {
+    let groups = kerning::kerning_groups(font);
+
+    match format {
+        OutputFormat::Text => {
+            if groups.is_empty() {
+                println!("No kerning groups found in UFO");
+                return Ok(());
+            }
+
+            println!("Kerning Groups:");
+            println!("---------------");
+
+            println!("\nLeft Groups (prefix: public.kern1):");
+            for group in groups.side(GroupSide::Left) {
+                println!("@{} → {}", group.name, group.members.join(", "));
+            }
+
+            println!("\nRight Groups (prefix: public.kern2):");
+            for group in groups.side(GroupSide::Right) {
+                println!("@{} → {}", group.name, group.members.join(", "));
+            }
+        }
+        OutputFormat::Json => {
+            let json: Vec<_> = groups
+                .iter()
+                .map(|group| {
+                    serde_json::json!({
+                        "name": group.full_name(),
+                        "short_name": group.name,
+                        "side": group.side.to_string(),
+                        "members": group.members,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "groups": json }))?);
+        }
+        OutputFormat::Tsv | OutputFormat::Csv => {
+            println!("{}", super::record(format, &["name", "side", "members"]));
+            for group in groups.iter() {
+                println!("{}", super::record(format, &[group.full_name(), group.side.to_string(), group.members.join(",")]));
+            }
+        }
+        OutputFormat::Matrix => format.ensure_not_matrix("show-kerning-groups")?,
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use anyhow::Result;
+use lilufo::grid::{ElementKind, ElementSelection, Grid};
+use lilufo::layers::LayerSelection;
+use lilufo::topology::Safeguard;
+
+/// Executes the SnapToGrid command
+pub fn execute(
+    ufo_path: &Path,
+    layers: &LayerSelection,
+    elements: &ElementSelection,
+    grid: &Grid,
+    safeguard: Option<Safeguard>,
+    dry_run: bool
+) -> Result<()> {
+    let mut totals: BTreeMap<ElementKind, usize> = BTreeMap::new();
+    let mut skipped = 0;
+    let results = lilufo::grid::snap_to_grid(ufo_path, layers, elements, grid, safeguard)?;
+    if !dry_run {
+        lilufo::grid::write_snapped(ufo_path, &results)?;
+    }
+
+    for result in results {
+        if result.skipped {
+            skipped += 1;
+            println!("{}: Skipped - snapping would damage its contours", result.path.display());
+        } else if dry_run {
+            if !result.moves.is_empty() || !result.issues.is_empty() {
+                println!("{}:", result.path.display());
+            }
+            for element_move in &result.moves {
+                println!("  {}", element_move);
+            }
+        } else if result.all_on_grid {
+            println!("{}: All points rounded to {}", result.path.display(), grid);
+        } else {
+            println!("{}: Warning - Not all points are on {}", result.path.display(), grid);
+        }
+        for issue in &result.issues {
+            println!("  {}", issue);
+        }
+        if result.nudged > 0 {
+            println!("  nudged {} points to keep contours intact", result.nudged);
+        }
+        for (kind, count) in result.changed {
+            *totals.entry(kind).or_default() += count;
+        }
+    }
+
+    if totals.is_empty() {
+        println!("No coordinates needed rounding");
+    } else {
+        let touched: Vec<String> = totals.iter().map(|(kind, count)| format!("{} {}", count, kind)).collect();
+        println!("Rounded coordinates: {}", touched.join(", "));
+    }
+    if skipped > 0 {
+        println!("Skipped {} glyphs with degenerate contours", skipped);
+    }
+    if dry_run {
+        println!("Dry run: no files were written");
+    }
+    Ok(())
+}
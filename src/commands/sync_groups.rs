@@ -0,0 +1,64 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use norad::Font;
+use lilufo::kerning::display_name;
+
+/// Executes the SyncGroups command
+pub fn execute(from: &Path, to: &[PathBuf], force: bool, dry_run: bool) -> Result<()> {
+    let source = Font::load(from).map_err(|e| anyhow::anyhow!("Failed to load {}: {}", from.display(), e))?;
+
+    let mut diverged = 0;
+    for target_path in to {
+        if to.len() > 1 {
+            println!("== {} ==", target_path.display());
+        }
+        let mut font =
+            Font::load(target_path).map_err(|e| anyhow::anyhow!("Failed to load {}: {}", target_path.display(), e))?;
+        let original = font.clone();
+        let sync = lilufo::sync::sync_groups(&source, &mut font)?;
+
+        for rename in &sync.renamed {
+            let pairs = sync.remapped_pairs.iter().filter(|(before, _)| {
+                before.first == rename.from || before.second == rename.from
+            });
+            println!(
+                "Renamed {} to {} ({} kerning pair(s) remapped)",
+                display_name(&rename.from),
+                display_name(&rename.to),
+                pairs.count()
+            );
+        }
+        for conflict in &sync.conflicts {
+            println!("Conflict: {}", conflict);
+        }
+        if !sync.conflicts.is_empty() && !force {
+            diverged += 1;
+            println!("Skipped {}: its groups have diverged (use --force to overwrite)", target_path.display());
+            continue;
+        }
+
+        if font == original {
+            println!("{} is already in sync", target_path.display());
+            continue;
+        }
+        println!(
+            "Synced {}: {} added, {} updated, {} renamed, {} removed group(s)",
+            target_path.display(),
+            sync.added.len(),
+            sync.updated.len(),
+            sync.renamed.len(),
+            sync.removed.len()
+        );
+        if dry_run {
+            super::print_font_diff(&lilufo::diff::diff_fonts(&original, &font));
+            println!("Dry run: {} was not modified", target_path.display());
+        } else {
+            lilufo::backup::replace_ufo(target_path, |staging| Ok(font.save(staging)?))?;
+        }
+    }
+
+    if diverged > 0 {
+        return Err(anyhow::anyhow!("{} of {} target(s) have diverged groups and were not changed", diverged, to.len()));
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use std::path::Path;
+use anyhow::{anyhow, Result};
+
+/// Executes the Undo command
+pub fn execute(ufo_path: &Path, dry_run: bool) -> Result<()> {
+    if dry_run {
+        let mut backups = lilufo::backup::backups(ufo_path)?;
+        let backup = backups
+            .pop()
+            .ok_or_else(|| anyhow!("No backups of {} to restore", ufo_path.display()))?;
+        let name = backup.file_name().unwrap_or_default().to_string_lossy();
+        println!("Dry run: would restore {} from backup {}", ufo_path.display(), name);
+        println!("{} older backup(s) would be left", backups.len());
+        return Ok(());
+    }
+
+    let backup = lilufo::backup::undo(ufo_path)?;
+    let remaining = lilufo::backup::backups(ufo_path)?.len();
+
+    let name = backup.file_name().unwrap_or_default().to_string_lossy();
+    println!("Restored {} from backup {}", ufo_path.display(), name);
+    println!("{} older backup(s) left", remaining);
+    Ok(())
+}
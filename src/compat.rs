@@ -0,0 +1,176 @@
+//! Interpolation compatibility between masters
+//!
+//! Usage:
+//! ```bash
+//! lilufo check-compat Regular.ufo Bold.ufo Black.ufo
+//! ```
+//!
+//! Masters can only be interpolated if every glyph has the same structure
+//! in each of them: the same contours with the same number and types of
+//! points, the same components and the same anchors. Groups must have the
+//! same members, and every kerning pair should exist in every master.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use norad::{Font, Glyph};
+use crate::kerning;
+
+/// The aspect of a glyph, group or pair that differs between masters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IncompatibilityKind {
+    /// The glyph is not in every master's default layer
+    MissingGlyph,
+    ContourCount,
+    PointCount,
+    PointType,
+    /// Component base glyphs, in order
+    Components,
+    /// Anchor names, in order
+    Anchors,
+    GroupMembers,
+    /// The pair is not kerned in every master
+    KerningPair,
+}
+
+impl fmt::Display for IncompatibilityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IncompatibilityKind::MissingGlyph => write!(f, "missing glyph"),
+            IncompatibilityKind::ContourCount => write!(f, "contour count"),
+            IncompatibilityKind::PointCount => write!(f, "point count"),
+            IncompatibilityKind::PointType => write!(f, "point type"),
+            IncompatibilityKind::Components => write!(f, "components"),
+            IncompatibilityKind::Anchors => write!(f, "anchors"),
+            IncompatibilityKind::GroupMembers => write!(f, "group members"),
+            IncompatibilityKind::KerningPair => write!(f, "kerning pair"),
+        }
+    }
+}
+
+/// One difference between masters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incompatibility {
+    pub kind: IncompatibilityKind,
+    /// The glyph, group or kerning pair the difference is in
+    pub subject: String,
+    /// Where within the glyph, e.g. `contour 0 point 3`
+    pub location: Option<String>,
+    /// What each master has, in the order the masters were given
+    pub values: Vec<String>,
+}
+
+/// Placeholder value for something a master does not have
+pub const MISSING: &str = "missing";
+
+/// Compares the default layers, groups and kerning of two or more masters
+pub fn check_compatibility(fonts: &[&Font]) -> Vec<Incompatibility> {
+    let mut incompatibilities = Vec::new();
+
+    let glyph_names: BTreeSet<&str> =
+        fonts.iter().flat_map(|font| font.default_layer().iter().map(|glyph| glyph.name().as_str())).collect();
+    for name in glyph_names {
+        let glyphs: Vec<Option<&Glyph>> = fonts.iter().map(|font| font.default_layer().get_glyph(name)).collect();
+        if glyphs.iter().any(Option::is_none) {
+            incompatibilities.push(Incompatibility {
+                kind: IncompatibilityKind::MissingGlyph,
+                subject: name.to_string(),
+                location: None,
+                values: glyphs.iter().map(|g| if g.is_some() { "present" } else { MISSING }.to_string()).collect(),
+            });
+            continue;
+        }
+        let glyphs: Vec<&Glyph> = glyphs.into_iter().flatten().collect();
+        compare_glyphs(name, &glyphs, &mut incompatibilities);
+    }
+
+    let group_names: BTreeSet<&str> =
+        fonts.iter().flat_map(|font| font.groups.keys().map(|name| name.as_str())).collect();
+    for name in group_names {
+        let members: Vec<String> = fonts
+            .iter()
+            .map(|font| match font.groups.get(name) {
+                Some(members) => members.iter().map(|m| m.as_str()).collect::<Vec<_>>().join(", "),
+                None => MISSING.to_string(),
+            })
+            .collect();
+        if !all_equal(&members) {
+            incompatibilities.push(Incompatibility {
+                kind: IncompatibilityKind::GroupMembers,
+                subject: name.to_string(),
+                location: None,
+                values: members,
+            });
+        }
+    }
+
+    let pairs: BTreeSet<(String, String)> = fonts
+        .iter()
+        .flat_map(|font| kerning::kerning_table(font).pairs.into_iter().map(|pair| (pair.first, pair.second)))
+        .collect();
+    for (first, second) in pairs {
+        let values: Vec<String> = fonts
+            .iter()
+            .map(|font| match font.kerning.get(first.as_str()).and_then(|s| s.get(second.as_str())) {
+                Some(value) => value.to_string(),
+                None => MISSING.to_string(),
+            })
+            .collect();
+        if values.iter().any(|value| value == MISSING) {
+            incompatibilities.push(Incompatibility {
+                kind: IncompatibilityKind::KerningPair,
+                subject: format!("{} {}", kerning::display_name(&first), kerning::display_name(&second)),
+                location: None,
+                values,
+            });
+        }
+    }
+
+    incompatibilities
+}
+
+fn compare_glyphs(name: &str, glyphs: &[&Glyph], incompatibilities: &mut Vec<Incompatibility>) {
+    let mut report = |kind, location: Option<String>, values: Vec<String>| {
+        incompatibilities.push(Incompatibility { kind, subject: name.to_string(), location, values });
+    };
+
+    let contour_counts: Vec<String> = glyphs.iter().map(|g| g.contours.len().to_string()).collect();
+    if !all_equal(&contour_counts) {
+        report(IncompatibilityKind::ContourCount, None, contour_counts);
+    } else {
+        for contour in 0..glyphs[0].contours.len() {
+            let point_counts: Vec<String> =
+                glyphs.iter().map(|g| g.contours[contour].points.len().to_string()).collect();
+            if !all_equal(&point_counts) {
+                report(IncompatibilityKind::PointCount, Some(format!("contour {}", contour)), point_counts);
+                continue;
+            }
+            for point in 0..glyphs[0].contours[contour].points.len() {
+                let types: Vec<String> =
+                    glyphs.iter().map(|g| g.contours[contour].points[point].typ.to_string()).collect();
+                if !all_equal(&types) {
+                    report(IncompatibilityKind::PointType, Some(format!("contour {} point {}", contour, point)), types);
+                }
+            }
+        }
+    }
+
+    let components: Vec<String> = glyphs
+        .iter()
+        .map(|g| g.components.iter().map(|c| c.base.as_str()).collect::<Vec<_>>().join(", "))
+        .collect();
+    if !all_equal(&components) {
+        report(IncompatibilityKind::Components, None, components);
+    }
+
+    let anchors: Vec<String> = glyphs
+        .iter()
+        .map(|g| g.anchors.iter().map(|a| a.name.as_ref().map_or("", |n| n.as_str())).collect::<Vec<_>>().join(", "))
+        .collect();
+    if !all_equal(&anchors) {
+        report(IncompatibilityKind::Anchors, None, anchors);
+    }
+}
+
+fn all_equal(values: &[String]) -> bool {
+    values.windows(2).all(|pair| pair[0] == pair[1])
+}
@@ -0,0 +1,376 @@
+//! Designspace documents: sources, validation, instances and creation
+//!
+//! Usage:
+//! ```bash
+//! # Run any command on every source UFO
+//! lilufo --designspace Family.designspace round-to-even
+//!
+//! # Check that every source UFO and layer exists
+//! lilufo --designspace Family.designspace check-designspace
+//!
+//! # Write the instance UFOs listed under <instances>
+//! lilufo --designspace Family.designspace generate-instances
+//!
+//! # Describe two masters on a weight axis
+//! lilufo create-designspace --out Family.designspace --axis wght:300:400:700 \
+//!     --source Light.ufo:wght=300 --source Bold.ufo:wght=700
+//! ```
+//!
+//! File names in a designspace are relative to the document. Instances are
+//! interpolated between the sources that share their location on every
+//! axis but one; designspaces where an instance needs sources that differ
+//! on several axes at once are reported as unsupported.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use anyhow::{anyhow, Result};
+use norad::designspace::{Axis, DesignSpaceDocument, Dimension, Instance, Source};
+use norad::fontinfo::StyleMapStyle;
+use norad::Font;
+use crate::{backup, interpolate, layers};
+
+/// Loads a designspace document
+pub fn load_designspace(path: &Path) -> Result<DesignSpaceDocument> {
+    DesignSpaceDocument::load(path).map_err(|e| anyhow!("Failed to load {}: {}", path.display(), e))
+}
+
+fn document_dir(designspace_path: &Path) -> &Path {
+    designspace_path.parent().unwrap_or(Path::new(""))
+}
+
+/// The UFO a source refers to, relative to the current directory
+pub fn source_path(designspace_path: &Path, source: &Source) -> PathBuf {
+    document_dir(designspace_path).join(&source.filename)
+}
+
+/// Lists the distinct source UFOs of a designspace, in document order.
+/// Sources that only point at a layer of a UFO share that UFO's entry.
+pub fn source_paths(designspace_path: &Path, document: &DesignSpaceDocument) -> Vec<PathBuf> {
+    let mut seen = BTreeSet::new();
+    document
+        .sources
+        .iter()
+        .map(|source| source_path(designspace_path, source))
+        .filter(|path| seen.insert(path.clone()))
+        .collect()
+}
+
+/// Lists everything in a designspace that does not resolve: missing source
+/// UFOs and layers, and locations naming undefined axes
+pub fn validate_designspace(designspace_path: &Path, document: &DesignSpaceDocument) -> Vec<String> {
+    let mut problems = Vec::new();
+    let axis_names: BTreeSet<&str> = document.axes.iter().map(|axis| axis.name.as_str()).collect();
+    let check_location = |owner: &str, location: &[Dimension], problems: &mut Vec<String>| {
+        for dimension in location {
+            if !axis_names.contains(dimension.name.as_str()) {
+                problems.push(format!("{}: location uses undefined axis '{}'", owner, dimension.name));
+            }
+        }
+    };
+
+    if document.sources.is_empty() {
+        problems.push("No sources defined".to_string());
+    }
+    for source in &document.sources {
+        let path = source_path(designspace_path, source);
+        let owner = source.name.clone().unwrap_or_else(|| source.filename.clone());
+        check_location(&owner, &source.location, &mut problems);
+
+        if !path.join("metainfo.plist").is_file() {
+            problems.push(format!("{}: UFO {} does not exist", owner, path.display()));
+            continue;
+        }
+        if let Some(layer) = &source.layer {
+            match layers::layer_dirs(&path) {
+                Ok(dirs) if dirs.iter().any(|dir| dir.name == *layer) => {}
+                Ok(_) => problems.push(format!("{}: layer '{}' does not exist in {}", owner, layer, path.display())),
+                Err(e) => problems.push(format!("{}: {}", owner, e)),
+            }
+        }
+    }
+    for (index, instance) in document.instances.iter().enumerate() {
+        let owner = instance_label(instance, index);
+        check_location(&owner, &instance.location, &mut problems);
+    }
+    problems
+}
+
+fn instance_label(instance: &Instance, index: usize) -> String {
+    instance
+        .name
+        .clone()
+        .or_else(|| match (&instance.familyname, &instance.stylename) {
+            (Some(family), Some(style)) => Some(format!("{} {}", family, style)),
+            (family, style) => family.clone().or_else(|| style.clone()),
+        })
+        .or_else(|| instance.filename.clone())
+        .unwrap_or_else(|| format!("instance {}", index))
+}
+
+/// Converts a user-space value to design space through the axis map
+fn to_design(axis: &Axis, user: f32) -> f32 {
+    let Some(map) = axis.map.as_ref().filter(|map| !map.is_empty()) else {
+        return user;
+    };
+    let mut map: Vec<_> = map.iter().collect();
+    map.sort_by(|a, b| a.input.total_cmp(&b.input));
+    if user <= map[0].input {
+        return map[0].output;
+    }
+    for pair in map.windows(2) {
+        if user <= pair[1].input {
+            let t = (user - pair[0].input) / (pair[1].input - pair[0].input);
+            return pair[0].output + (pair[1].output - pair[0].output) * t;
+        }
+    }
+    map[map.len() - 1].output
+}
+
+/// A location's design-space value on every axis, defaulting missing ones
+fn design_location(axes: &[Axis], location: &[Dimension]) -> Vec<f64> {
+    axes.iter()
+        .map(|axis| {
+            let dimension = location.iter().find(|dimension| dimension.name == axis.name);
+            let value = match dimension {
+                Some(Dimension { xvalue: Some(x), .. }) => *x,
+                Some(Dimension { uservalue: Some(user), .. }) => to_design(axis, *user),
+                _ => to_design(axis, axis.default),
+            };
+            value as f64
+        })
+        .collect()
+}
+
+/// An instance UFO that was written
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedInstance {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Interpolates and saves every instance of a designspace that has a
+/// filename; the others only name a variable font's instances. Nothing is
+/// written if any instance fails.
+pub fn generate_instances(designspace_path: &Path, dry_run: bool) -> Result<Vec<GeneratedInstance>> {
+    let document = load_designspace(designspace_path)?;
+    let problems = validate_designspace(designspace_path, &document);
+    if !problems.is_empty() {
+        return Err(anyhow!("Invalid designspace:\n  {}", problems.join("\n  ")));
+    }
+
+    // Sparse layer sources only hold a few glyphs, so they cannot be masters here
+    let masters: Vec<(&Source, Vec<f64>)> = document
+        .sources
+        .iter()
+        .filter(|source| source.layer.is_none())
+        .map(|source| (source, design_location(&document.axes, &source.location)))
+        .collect();
+    let mut fonts = Vec::new();
+    for (source, _) in &masters {
+        let path = source_path(designspace_path, source);
+        fonts.push(Font::load(&path).map_err(|e| anyhow!("Failed to load {}: {}", path.display(), e))?);
+    }
+
+    let mut instances = Vec::new();
+    for (index, instance) in document.instances.iter().enumerate() {
+        let Some(filename) = &instance.filename else {
+            continue;
+        };
+        let label = instance_label(instance, index);
+        let location = design_location(&document.axes, &instance.location);
+        let mut font = instance_font(&masters, &fonts, &location)
+            .map_err(|e| anyhow!("{}: {}", label, e))?;
+
+        font.font_info.family_name = instance.familyname.clone().or(font.font_info.family_name);
+        font.font_info.style_name = instance.stylename.clone().or(font.font_info.style_name);
+        font.font_info.postscript_font_name =
+            instance.postscriptfontname.clone().or(font.font_info.postscript_font_name);
+        font.font_info.style_map_family_name =
+            instance.stylemapfamilyname.clone().or(font.font_info.style_map_family_name);
+        if let Some(style_map) = &instance.stylemapstylename {
+            font.font_info.style_map_style_name = Some(match style_map.as_str() {
+                "regular" => StyleMapStyle::Regular,
+                "italic" => StyleMapStyle::Italic,
+                "bold" => StyleMapStyle::Bold,
+                "bold italic" => StyleMapStyle::BoldItalic,
+                _ => return Err(anyhow!("{}: invalid stylemapstylename '{}'", label, style_map)),
+            });
+        }
+
+        instances.push((GeneratedInstance { name: label, path: document_dir(designspace_path).join(filename) }, font));
+    }
+
+    if !dry_run {
+        for (instance, font) in &instances {
+            if instance.path.exists() {
+                backup::replace_ufo(&instance.path, |staging| Ok(font.save(staging)?))?;
+            } else {
+                if let Some(parent) = instance.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                font.save(&instance.path)?;
+            }
+        }
+    }
+    Ok(instances.into_iter().map(|(instance, _)| instance).collect())
+}
+
+/// Interpolates the font at `location` from the masters that lie on a line
+/// through it
+fn instance_font(masters: &[(&Source, Vec<f64>)], fonts: &[Font], location: &[f64]) -> Result<Font> {
+    if let Some(index) = masters.iter().position(|(_, master)| master == location) {
+        return Ok(fonts[index].clone());
+    }
+
+    for axis in 0..location.len() {
+        // Masters that match the instance on every other axis
+        let mut line: Vec<(f64, &Font)> = masters
+            .iter()
+            .zip(fonts)
+            .filter(|((_, master), _)| (0..location.len()).all(|other| other == axis || master[other] == location[other]))
+            .map(|((_, master), font)| (master[axis], font))
+            .collect();
+        line.sort_by(|a, b| a.0.total_cmp(&b.0));
+        line.dedup_by(|a, b| a.0 == b.0);
+        if line.len() < 2 {
+            continue;
+        }
+
+        let value = location[axis];
+        let segment = line
+            .windows(2)
+            .position(|pair| value <= pair[1].0)
+            .unwrap_or(line.len() - 2);
+        let ((start, a), (end, b)) = (line[segment], line[segment + 1]);
+        return interpolate::interpolate(a, b, (value - start) / (end - start));
+    }
+
+    Err(anyhow!("no two sources lie on a single axis through the instance location"))
+}
+
+/// An axis given on the command line as `tag:minimum:default:maximum`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisSpec {
+    pub tag: String,
+    pub minimum: f32,
+    pub default: f32,
+    pub maximum: f32,
+}
+
+impl FromStr for AxisSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let [tag, minimum, default, maximum] = parts.as_slice() else {
+            return Err(anyhow!("axis must be given as tag:minimum:default:maximum"));
+        };
+        Ok(AxisSpec {
+            tag: tag.to_string(),
+            minimum: minimum.parse()?,
+            default: default.parse()?,
+            maximum: maximum.parse()?,
+        })
+    }
+}
+
+/// A source given on the command line as `path:tag=value,tag=value`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSpec {
+    pub path: PathBuf,
+    pub location: Vec<(String, f32)>,
+}
+
+impl FromStr for SourceSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (path, location) = s
+            .rsplit_once(':')
+            .ok_or_else(|| anyhow!("source must be given as path:tag=value[,tag=value...]"))?;
+        let location = location
+            .split(',')
+            .map(|dimension| {
+                let (tag, value) = dimension
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("location must be given as tag=value, got '{}'", dimension))?;
+                Ok((tag.trim().to_string(), value.trim().parse()?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SourceSpec { path: PathBuf::from(path), location })
+    }
+}
+
+/// The conventional axis name for a registered tag
+fn axis_name(tag: &str) -> String {
+    match tag {
+        "wght" => "Weight",
+        "wdth" => "Width",
+        "ital" => "Italic",
+        "slnt" => "Slant",
+        "opsz" => "Optical size",
+        _ => tag,
+    }
+    .to_string()
+}
+
+/// Builds a designspace from axes and sources and, unless `dry_run` is set,
+/// writes it to `out`. Source paths are stored relative to the document's
+/// directory when possible.
+pub fn create_designspace(
+    out: &Path,
+    axes: &[AxisSpec],
+    sources: &[SourceSpec],
+    dry_run: bool,
+) -> Result<DesignSpaceDocument> {
+    let axes: Vec<Axis> = axes
+        .iter()
+        .map(|axis| Axis {
+            name: axis_name(&axis.tag),
+            tag: axis.tag.clone(),
+            default: axis.default,
+            minimum: Some(axis.minimum),
+            maximum: Some(axis.maximum),
+            ..Default::default()
+        })
+        .collect();
+
+    let mut document_sources = Vec::new();
+    for spec in sources {
+        let font = Font::load(&spec.path).map_err(|e| anyhow!("Failed to load {}: {}", spec.path.display(), e))?;
+        let mut location = Vec::new();
+        for (tag, value) in &spec.location {
+            let axis = axes
+                .iter()
+                .find(|axis| axis.tag == *tag)
+                .ok_or_else(|| anyhow!("{}: axis '{}' is not defined", spec.path.display(), tag))?;
+            location.push(Dimension { name: axis.name.clone(), xvalue: Some(*value), ..Default::default() });
+        }
+        let filename = relative_to(&spec.path, document_dir(out));
+        document_sources.push(Source {
+            familyname: font.font_info.family_name.clone(),
+            stylename: font.font_info.style_name.clone(),
+            name: Some(format!("master.{}", document_sources.len())),
+            filename: filename.to_string_lossy().into_owned(),
+            location,
+            ..Default::default()
+        });
+    }
+
+    let document = DesignSpaceDocument { format: 5.0, axes, sources: document_sources, ..Default::default() };
+    if !dry_run {
+        document.save(out).map_err(|e| anyhow!("Failed to write {}: {}", out.display(), e))?;
+    }
+    Ok(document)
+}
+
+/// `path` relative to `base` if it lies inside it, otherwise absolute so it
+/// still resolves from the document's directory
+fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let base = if base.as_os_str().is_empty() { Path::new(".") } else { base };
+    match (path.canonicalize(), base.canonicalize()) {
+        (Ok(path), Ok(base)) => path.strip_prefix(&base).map(Path::to_path_buf).unwrap_or(path),
+        _ => path.to_path_buf(),
+    }
+}
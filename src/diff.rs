@@ -0,0 +1,119 @@
+//! Semantic differences between two versions of a font
+//!
+//! Used by `--dry-run` to show what a command would change without saving:
+//! kerning pairs added, removed or revalued, groups created, deleted or
+//! given different members, and edits to `features.fea`.
+
+use std::collections::BTreeSet;
+use norad::Font;
+use crate::kerning::{self, KerningChange, KerningPair};
+
+/// A group that exists in either version of the font
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupChange {
+    pub name: String,
+    /// Members before the change, or `None` if the group is new
+    pub before: Option<Vec<String>>,
+    /// Members after the change, or `None` if the group was removed
+    pub after: Option<Vec<String>>,
+}
+
+impl GroupChange {
+    /// Members the group gained
+    pub fn added_members(&self) -> Vec<&str> {
+        let before = self.before.as_deref().unwrap_or_default();
+        let after = self.after.as_deref().unwrap_or_default();
+        after.iter().filter(|m| !before.contains(m)).map(String::as_str).collect()
+    }
+
+    /// Members the group lost
+    pub fn removed_members(&self) -> Vec<&str> {
+        let before = self.before.as_deref().unwrap_or_default();
+        let after = self.after.as_deref().unwrap_or_default();
+        before.iter().filter(|m| !after.contains(m)).map(String::as_str).collect()
+    }
+}
+
+/// A line of a `features.fea` diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineChange {
+    Removed(String),
+    Added(String),
+}
+
+/// Everything that differs between two versions of a font
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontDiff {
+    pub added_pairs: Vec<KerningPair>,
+    pub removed_pairs: Vec<KerningPair>,
+    pub changed_pairs: Vec<KerningChange>,
+    pub groups: Vec<GroupChange>,
+    /// Changed lines of `features.fea`, between the unchanged start and end
+    pub features: Vec<LineChange>,
+}
+
+impl FontDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_pairs.is_empty()
+            && self.removed_pairs.is_empty()
+            && self.changed_pairs.is_empty()
+            && self.groups.is_empty()
+            && self.features.is_empty()
+    }
+}
+
+/// Compares the kerning, groups and features of two fonts
+pub fn diff_fonts(before: &Font, after: &Font) -> FontDiff {
+    let mut diff = FontDiff::default();
+
+    for pair in kerning::kerning_table(after).iter() {
+        match before.kerning.get(pair.first.as_str()).and_then(|s| s.get(pair.second.as_str())) {
+            None => diff.added_pairs.push(pair.clone()),
+            Some(&old_value) if old_value != pair.value => diff.changed_pairs.push(KerningChange {
+                first: pair.first.clone(),
+                second: pair.second.clone(),
+                old_value,
+                new_value: pair.value,
+            }),
+            Some(_) => {}
+        }
+    }
+    for pair in kerning::kerning_table(before).iter() {
+        if after.kerning.get(pair.first.as_str()).and_then(|s| s.get(pair.second.as_str())).is_none() {
+            diff.removed_pairs.push(pair.clone());
+        }
+    }
+
+    let names: BTreeSet<&str> = before.groups.keys().chain(after.groups.keys()).map(|name| name.as_str()).collect();
+    for name in names {
+        let members = |font: &Font| {
+            font.groups.get(name).map(|members| members.iter().map(|m| m.to_string()).collect::<Vec<_>>())
+        };
+        let (old_members, new_members) = (members(before), members(after));
+        if old_members != new_members {
+            diff.groups.push(GroupChange { name: name.to_string(), before: old_members, after: new_members });
+        }
+    }
+
+    diff.features = diff_lines(&before.features, &after.features);
+    diff
+}
+
+/// A minimal line diff: everything between the common first and last lines
+fn diff_lines(before: &str, after: &str) -> Vec<LineChange> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    old[prefix..old.len() - suffix]
+        .iter()
+        .map(|line| LineChange::Removed(line.to_string()))
+        .chain(new[prefix..new.len() - suffix].iter().map(|line| LineChange::Added(line.to_string())))
+        .collect()
+}
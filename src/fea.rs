@@ -0,0 +1,203 @@
+//! OpenType feature syntax export for UFO kerning
+//!
+//! Usage:
+//! ```bash
+//! # Print the generated classes and kern feature
+//! lilufo --ufo-path font.ufo export-fea
+//!
+//! # Merge them into the UFO's features.fea between marker comments
+//! lilufo --ufo-path font.ufo export-fea --merge
+//! ```
+//!
+//! Groups become `@class` definitions (`public.kern1.O` is written as
+//! `@kern1.O`) and kerning becomes `pos` rules inside a `kern` feature.
+//! Glyph-to-glyph pairs come first, then glyph-to-group and group-to-glyph
+//! exceptions as `enum pos`, then class-to-class pairs. feaLib keeps the
+//! first rule for a glyph pair, so this order gives the same precedence as
+//! the UFO. Groups whose names only differ in characters FEA does not
+//! allow get a numeric suffix (`@kern1.a_b_2`) to keep their classes apart.
+//! FEA value records are integers, so fractional kerning is rounded.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use norad::Font;
+use crate::kerning::{self, GroupSide, KerningPair};
+
+/// Marks the start of the block managed by `export-fea --merge`
+pub const FEA_BEGIN_MARKER: &str = "# lilufo kerning begin";
+/// Marks the end of the block managed by `export-fea --merge`
+pub const FEA_END_MARKER: &str = "# lilufo kerning end";
+
+/// Returns the FEA class name for a UFO group name
+pub fn class_name(group_name: &str) -> String {
+    let name = match GroupSide::split_full_name(group_name) {
+        Some((GroupSide::Left, name)) => format!("kern1.{}", name),
+        Some((GroupSide::Right, name)) => format!("kern2.{}", name),
+        None => group_name.to_string(),
+    };
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '_' { c } else { '_' })
+        .collect();
+    format!("@{}", name)
+}
+
+/// Assigns every group a distinct FEA class name
+fn class_names(font: &Font) -> BTreeMap<String, String> {
+    let mut taken = BTreeSet::new();
+    let mut names = BTreeMap::new();
+    for group_name in font.groups.keys() {
+        let base = class_name(group_name);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !taken.insert(name.clone()) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        names.insert(group_name.to_string(), name);
+    }
+    names
+}
+
+/// Generates `@class` definitions for the font's groups and a `kern`
+/// feature for its kerning
+pub fn export_fea(font: &Font) -> String {
+    let mut fea = String::new();
+
+    // Empty classes are invalid FEA, so neither define nor reference them
+    let is_usable_group = |name: &str| font.groups.get(name).is_some_and(|members| !members.is_empty());
+    let classes = class_names(font);
+
+    for (name, members) in &font.groups {
+        if members.is_empty() {
+            continue;
+        }
+        let members: Vec<&str> = members.iter().map(|m| m.as_str()).collect();
+        let _ = writeln!(fea, "{} = [{}];", classes[name.as_str()], members.join(" "));
+    }
+
+    let table = kerning::kerning_table(font);
+    let is_group = |key: &str| GroupSide::split_full_name(key).is_some();
+    let mut glyph_pairs = Vec::new();
+    let mut glyph_exceptions = Vec::new();
+    let mut group_exceptions = Vec::new();
+    let mut class_pairs = Vec::new();
+    for pair in table.iter() {
+        if (is_group(&pair.first) && !is_usable_group(&pair.first))
+            || (is_group(&pair.second) && !is_usable_group(&pair.second))
+        {
+            continue;
+        }
+        match (is_group(&pair.first), is_group(&pair.second)) {
+            (false, false) => glyph_pairs.push(pair),
+            (false, true) => glyph_exceptions.push(pair),
+            (true, false) => group_exceptions.push(pair),
+            (true, true) => class_pairs.push(pair),
+        }
+    }
+
+    let side = |key: &str| if is_group(key) { classes[key].clone() } else { key.to_string() };
+    let rule = |pair: &KerningPair| format!("{} {} {}", side(&pair.first), side(&pair.second), pair.value.round() as i64);
+
+    if !fea.is_empty() {
+        fea.push('\n');
+    }
+    fea.push_str("feature kern {\n");
+    for pair in glyph_pairs {
+        let _ = writeln!(fea, "    pos {};", rule(pair));
+    }
+    // A glyph–group pair outranks a group–glyph pair for the same glyphs
+    for pair in glyph_exceptions.into_iter().chain(group_exceptions) {
+        let _ = writeln!(fea, "    enum pos {};", rule(pair));
+    }
+    for pair in class_pairs {
+        let _ = writeln!(fea, "    pos {};", rule(pair));
+    }
+    fea.push_str("} kern;\n");
+
+    fea
+}
+
+/// Places `block` between the lilufo marker comments in `features`,
+/// replacing a previously merged block or appending one if none exists
+pub fn merge_into_features(features: &str, block: &str) -> String {
+    let wrapped = format!("{}\n{}{}\n", FEA_BEGIN_MARKER, block, FEA_END_MARKER);
+
+    if let (Some(begin), Some(end)) = (features.find(FEA_BEGIN_MARKER), features.find(FEA_END_MARKER)) {
+        if begin < end {
+            let after = &features[end + FEA_END_MARKER.len()..];
+            let after = after.strip_prefix('\n').unwrap_or(after);
+            return format!("{}{}{}", &features[..begin], wrapped, after);
+        }
+    }
+
+    if features.is_empty() {
+        wrapped
+    } else if features.ends_with('\n') {
+        format!("{}\n{}", features, wrapped)
+    } else {
+        format!("{}\n\n{}", features, wrapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kerning::test_support::font;
+
+    fn rules(fea: &str) -> Vec<&str> {
+        fea.lines().map(str::trim).filter(|line| line.contains("pos ")).collect()
+    }
+
+    #[test]
+    fn glyph_group_exceptions_come_before_group_glyph_exceptions() {
+        // `v` sorts after `public.kern1.v`, so key order would put the group–glyph pair first
+        let font = font(
+            &[],
+            &[("public.kern1.v", &["v", "w"]), ("public.kern2.o", &["o", "e"])],
+            &[("public.kern1.v", "o", -10.0), ("v", "public.kern2.o", -30.0), ("public.kern1.v", "public.kern2.o", -5.0)],
+        );
+        assert_eq!(
+            rules(&export_fea(&font)),
+            ["enum pos v @kern2.o -30;", "enum pos @kern1.v o -10;", "pos @kern1.v @kern2.o -5;"]
+        );
+    }
+
+    #[test]
+    fn glyph_pairs_come_first_and_values_are_rounded() {
+        let font = font(&[], &[("public.kern2.o", &["o"])], &[("T", "public.kern2.o", -20.0), ("T", "o", -12.6)]);
+        assert_eq!(rules(&export_fea(&font)), ["pos T o -13;", "enum pos T @kern2.o -20;"]);
+    }
+
+    #[test]
+    fn colliding_class_names_get_distinct_suffixes() {
+        let font = font(
+            &[],
+            &[("public.kern1.a-b", &["a"]), ("public.kern1.a_b", &["b"]), ("public.kern1.a_b_2", &["c"])],
+            &[("public.kern1.a-b", "x", 1.0), ("public.kern1.a_b", "x", 2.0)],
+        );
+        let fea = export_fea(&font);
+        assert!(fea.contains("@kern1.a_b = [a];"));
+        assert!(fea.contains("@kern1.a_b_2 = [b];"));
+        assert!(fea.contains("@kern1.a_b_2_2 = [c];"));
+        assert_eq!(rules(&fea), ["enum pos @kern1.a_b x 1;", "enum pos @kern1.a_b_2 x 2;"]);
+    }
+
+    #[test]
+    fn empty_groups_are_neither_defined_nor_referenced() {
+        let font = font(&[], &[("public.kern1.empty", &[])], &[("public.kern1.empty", "x", 5.0)]);
+        let fea = export_fea(&font);
+        assert!(!fea.contains("@kern1.empty"));
+        assert!(rules(&fea).is_empty());
+    }
+
+    #[test]
+    fn merge_replaces_the_previous_block() {
+        let merged = merge_into_features("languagesystem DFLT dflt;\n", "old\n");
+        let merged = merge_into_features(&merged, "new\n");
+        assert_eq!(
+            merged,
+            format!("languagesystem DFLT dflt;\n\n{}\nnew\n{}\n", FEA_BEGIN_MARKER, FEA_END_MARKER)
+        );
+    }
+}
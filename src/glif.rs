@@ -0,0 +1,272 @@
+//! A byte-preserving editor for `.glif` attributes
+//!
+//! Tools that only adjust coordinates should not reformat the files they
+//! touch: re-serialising through an XML tree changes indentation, attribute
+//! order, the XML declaration and comments, which makes for noisy diffs.
+//! [`GlifSource`] scans the start tags of a document and records where each
+//! attribute value sits, so edits replace exactly those bytes and leave the
+//! rest of the file alone.
+
+use std::ops::Range;
+use anyhow::{anyhow, Result};
+
+/// An attribute of a start tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: String,
+    /// The raw value, without quotes or entity decoding
+    pub value: String,
+    span: Range<usize>,
+}
+
+/// A start (or self-closing) tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    /// Index of the enclosing tag, `None` for the root element
+    pub parent: Option<usize>,
+}
+
+impl Tag {
+    /// The raw value of an attribute
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|a| a.name == name).map(|a| a.value.as_str())
+    }
+}
+
+/// The text of a `.glif` file together with the position of every tag
+#[derive(Debug, Clone)]
+pub struct GlifSource {
+    text: String,
+    tags: Vec<Tag>,
+    /// Pending value replacements as (byte span, new value)
+    edits: Vec<(Range<usize>, String)>,
+}
+
+impl GlifSource {
+    /// Scans a document's tags
+    pub fn parse(text: String) -> Result<GlifSource> {
+        let tags = scan_tags(&text)?;
+        Ok(GlifSource { text, tags, edits: Vec::new() })
+    }
+
+    /// Every start tag in document order
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    /// Indices of the tags directly inside `parent` that are called `name`
+    pub fn children<'a>(&'a self, parent: usize, name: &'a str) -> impl Iterator<Item = usize> + 'a {
+        (parent + 1..self.tags.len()).filter(move |&i| self.tags[i].parent == Some(parent) && self.tags[i].name == name)
+    }
+
+    /// Replaces the value of an existing attribute. Returns `false` if the
+    /// tag has no such attribute.
+    pub fn set_attribute(&mut self, tag: usize, name: &str, value: &str) -> bool {
+        let Some(attribute) = self.tags[tag].attributes.iter_mut().find(|a| a.name == name) else {
+            return false;
+        };
+        if attribute.value != value {
+            attribute.value = value.to_string();
+            self.edits.retain(|(span, _)| *span != attribute.span);
+            self.edits.push((attribute.span.clone(), value.to_string()));
+        }
+        true
+    }
+
+    /// Whether any attribute has been changed
+    pub fn is_modified(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    /// The document with every edit applied
+    pub fn to_text(&self) -> String {
+        let mut edits: Vec<&(Range<usize>, String)> = self.edits.iter().collect();
+        edits.sort_by_key(|(span, _)| span.start);
+
+        let mut text = String::with_capacity(self.text.len());
+        let mut position = 0;
+        for (span, value) in edits {
+            text.push_str(&self.text[position..span.start]);
+            text.push_str(value);
+            position = span.end;
+        }
+        text.push_str(&self.text[position..]);
+        text
+    }
+}
+
+fn scan_tags(text: &str) -> Result<Vec<Tag>> {
+    let bytes = text.as_bytes();
+    let error = |position: usize| anyhow!("Malformed XML at byte {}", position);
+    let mut tags: Vec<Tag> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    let mut position = 0;
+
+    while let Some(offset) = text[position..].find('<') {
+        let start = position + offset;
+        let rest = &text[start..];
+
+        // Markup without attributes we care about
+        let skip_to = |terminator: &str| {
+            rest.find(terminator).map(|end| start + end + terminator.len()).ok_or_else(|| error(start))
+        };
+        if rest.starts_with("<!--") {
+            position = skip_to("-->")?;
+            continue;
+        }
+        if rest.starts_with("<![CDATA[") {
+            position = skip_to("]]>")?;
+            continue;
+        }
+        if rest.starts_with("<?") {
+            position = skip_to("?>")?;
+            continue;
+        }
+        if rest.starts_with("<!") {
+            position = skip_to(">")?;
+            continue;
+        }
+        if rest.starts_with("</") {
+            open.pop().ok_or_else(|| error(start))?;
+            position = skip_to(">")?;
+            continue;
+        }
+
+        let mut i = start + 1;
+        let name_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'/' && bytes[i] != b'>' {
+            i += 1;
+        }
+        let name = text[name_start..i].to_string();
+        if name.is_empty() {
+            return Err(error(start));
+        }
+
+        let mut attributes = Vec::new();
+        let self_closing = loop {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match bytes.get(i) {
+                Some(b'>') => break false,
+                Some(b'/') if bytes.get(i + 1) == Some(&b'>') => {
+                    i += 1;
+                    break true;
+                }
+                Some(_) => {}
+                None => return Err(error(start)),
+            }
+
+            let attribute_start = i;
+            while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'=' {
+                i += 1;
+            }
+            let attribute_name = text[attribute_start..i].to_string();
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if bytes.get(i) != Some(&b'=') || attribute_name.is_empty() {
+                return Err(error(attribute_start));
+            }
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let quote = match bytes.get(i) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => quote,
+                _ => return Err(error(i)),
+            };
+            let value_start = i + 1;
+            let value_end = text[value_start..]
+                .find(quote as char)
+                .map(|end| value_start + end)
+                .ok_or_else(|| error(attribute_start))?;
+            attributes.push(Attribute {
+                name: attribute_name,
+                value: text[value_start..value_end].to_string(),
+                span: value_start..value_end,
+            });
+            i = value_end + 1;
+        };
+
+        let index = tags.len();
+        tags.push(Tag { name, attributes, parent: open.last().copied() });
+        if !self_closing {
+            open.push(index);
+        }
+        position = i + 1;
+    }
+
+    if !open.is_empty() {
+        return Err(anyhow!("Unclosed <{}> element", tags[*open.last().unwrap()].name));
+    }
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLYPH: &str = r#"<?xml version='1.0' encoding='UTF-8'?>
+<glyph name="a" format="2">
+  <!-- <point x="0" y="0"/> -->
+  <advance width="501"/>
+  <outline>
+    <contour>
+      <point x='11' y="-3" type="line"/>
+      <point   y="7.5"  x="20" type="curve" smooth="yes"/>
+    </contour>
+  </outline>
+</glyph>
+"#;
+
+    fn points(source: &GlifSource) -> Vec<usize> {
+        let outline = source.children(0, "outline").next().unwrap();
+        let contour = source.children(outline, "contour").next().unwrap();
+        source.children(contour, "point").collect()
+    }
+
+    #[test]
+    fn scans_tags_but_not_comments() {
+        let source = GlifSource::parse(GLYPH.to_string()).unwrap();
+        let names: Vec<&str> = source.tags().iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(names, ["glyph", "advance", "outline", "contour", "point", "point"]);
+        let points = points(&source);
+        assert_eq!(source.tags()[points[0]].attribute("x"), Some("11"));
+        assert_eq!(source.tags()[points[1]].attribute("y"), Some("7.5"));
+    }
+
+    #[test]
+    fn edits_only_replace_attribute_values() {
+        let mut source = GlifSource::parse(GLYPH.to_string()).unwrap();
+        let points = points(&source);
+        assert!(source.set_attribute(points[0], "x", "12"));
+        assert!(source.set_attribute(points[1], "y", "8"));
+        assert!(!source.set_attribute(points[1], "name", "top"));
+        assert!(source.is_modified());
+        let expected = GLYPH.replace("x='11'", "x='12'").replace(r#"y="7.5""#, r#"y="8""#);
+        assert_eq!(source.to_text(), expected);
+    }
+
+    #[test]
+    fn setting_the_same_value_is_not_an_edit() {
+        let mut source = GlifSource::parse(GLYPH.to_string()).unwrap();
+        let point = points(&source)[0];
+        assert!(source.set_attribute(point, "x", "11"));
+        assert!(!source.is_modified());
+
+        // Setting a value back cancels the earlier edit's output
+        source.set_attribute(point, "x", "12");
+        source.set_attribute(point, "x", "11");
+        assert_eq!(source.to_text(), GLYPH);
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        assert!(GlifSource::parse("<glyph><outline></glyph>".to_string()).is_err());
+        assert!(GlifSource::parse("<glyph name=a/>".to_string()).is_err());
+        assert!(GlifSource::parse("<glyph name=\"a/>".to_string()).is_err());
+    }
+}
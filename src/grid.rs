@@ -0,0 +1,557 @@
+//! Grid snapping for glyph coordinates
+//!
+//! Usage:
+//! ```bash
+//! # Round all points to even integers (a 2-unit grid)
+//! lilufo --ufo-path font.ufo round-to-even
+//!
+//! # Snap to an 8-unit grid shifted by 4 units horizontally
+//! lilufo --ufo-path font.ufo snap-to-grid --grid 8 --offset 4,0
+//!
+//! # Plain integer rounding
+//! lilufo --ufo-path font.ufo snap-to-grid --grid 1
+//!
+//! # List every coordinate that is off an 8-unit grid
+//! lilufo --ufo-path font.ufo check-grid --grid 8
+//! ```
+//!
+//! Positions (points, anchors, guidelines) snap to `offset + k * grid`.
+//! Lengths (advance width/height, component offsets) snap to multiples of
+//! the grid, since shifting them by the offset would change their size.
+//!
+//! Snapped contours are checked for degenerate results (see
+//! [`crate::topology`]); `--safeguard nudge|skip` decides what happens to
+//! glyphs that have them, otherwise they are only reported.
+
+use std::path::{Path, PathBuf};
+use std::fs;
+use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use crate::backup;
+use crate::glif::GlifSource;
+use crate::layers::{self, LayerSelection};
+use crate::topology::{self, Contour, ContourPoint, Safeguard, TopologyIssue};
+
+/// How far from a grid line, in grid steps, a value still counts as on it;
+/// absorbs floating-point error on fractional grids such as 0.1
+const GRID_TOLERANCE: f64 = 1e-6;
+
+/// A snapping grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid {
+    /// Distance between grid lines
+    pub size: f64,
+    pub x_offset: f64,
+    pub y_offset: f64,
+}
+
+/// What a coordinate attribute measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// A horizontal position
+    X,
+    /// A vertical position
+    Y,
+    /// A length or displacement, which ignores the grid offset
+    Length,
+}
+
+impl Grid {
+    /// The 2-unit grid used by `round-to-even`
+    pub const EVEN: Grid = Grid { size: 2.0, x_offset: 0.0, y_offset: 0.0 };
+
+    pub fn new(size: f64, x_offset: f64, y_offset: f64) -> Result<Grid> {
+        if !(size > 0.0 && size.is_finite()) {
+            return Err(anyhow!("Grid size must be a positive number"));
+        }
+        Ok(Grid { size, x_offset, y_offset })
+    }
+
+    fn offset(&self, axis: Axis) -> f64 {
+        match axis {
+            Axis::X => self.x_offset,
+            Axis::Y => self.y_offset,
+            Axis::Length => 0.0,
+        }
+    }
+
+    /// Snaps a value to the nearest grid line
+    pub fn snap(&self, value: f64, axis: Axis) -> f64 {
+        let offset = self.offset(axis);
+        let snapped = ((value - offset) / self.size).round() * self.size + offset;
+        // Drop floating-point noise (0.30000000000000004) and negative zero
+        // so the value is written the way it would be typed
+        let snapped = (snapped * 1e9).round() / 1e9;
+        if snapped == 0.0 {
+            0.0
+        } else {
+            snapped
+        }
+    }
+
+    /// Whether a value lies on a grid line
+    pub fn is_on_grid(&self, value: f64, axis: Axis) -> bool {
+        let steps = (value - self.offset(axis)) / self.size;
+        (steps - steps.round()).abs() < GRID_TOLERANCE
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.x_offset == 0.0 && self.y_offset == 0.0 {
+            if self.size == 2.0 {
+                return write!(f, "even integers");
+            }
+            if self.size == 1.0 {
+                return write!(f, "integers");
+            }
+        }
+        write!(f, "the {}-unit grid", self.size)?;
+        if self.x_offset != 0.0 || self.y_offset != 0.0 {
+            write!(f, " offset by ({}, {})", self.x_offset, self.y_offset)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses an `x,y` grid offset
+pub fn parse_offset(s: &str) -> Result<(f64, f64)> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| anyhow!("offset must be given as x,y"))?;
+    Ok((f64::from_str(x.trim())?, f64::from_str(y.trim())?))
+}
+
+/// Kinds of GLIF elements whose coordinates can be rounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ElementKind {
+    /// `<point>` `x`/`y`
+    Point,
+    /// `<advance>` `width`/`height`
+    Advance,
+    /// `<anchor>` `x`/`y`
+    Anchor,
+    /// `<guideline>` `x`/`y`
+    Guideline,
+    /// `<component>` `xOffset`/`yOffset`
+    Component,
+}
+
+impl ElementKind {
+    fn from_element_name(name: &str) -> Option<ElementKind> {
+        match name {
+            "point" => Some(ElementKind::Point),
+            "advance" => Some(ElementKind::Advance),
+            "anchor" => Some(ElementKind::Anchor),
+            "guideline" => Some(ElementKind::Guideline),
+            "component" => Some(ElementKind::Component),
+            _ => None,
+        }
+    }
+
+    /// The GLIF element name, e.g. `point`
+    pub fn element_name(self) -> &'static str {
+        match self {
+            ElementKind::Point => "point",
+            ElementKind::Advance => "advance",
+            ElementKind::Anchor => "anchor",
+            ElementKind::Guideline => "guideline",
+            ElementKind::Component => "component",
+        }
+    }
+
+    /// The coordinate attributes rounding rewrites on this element
+    pub fn attributes(self) -> &'static [(&'static str, Axis)] {
+        match self {
+            ElementKind::Point | ElementKind::Anchor | ElementKind::Guideline => &[("x", Axis::X), ("y", Axis::Y)],
+            ElementKind::Advance => &[("width", Axis::Length), ("height", Axis::Length)],
+            ElementKind::Component => &[("xOffset", Axis::Length), ("yOffset", Axis::Length)],
+        }
+    }
+}
+
+impl fmt::Display for ElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementKind::Point => write!(f, "points"),
+            ElementKind::Advance => write!(f, "advances"),
+            ElementKind::Anchor => write!(f, "anchors"),
+            ElementKind::Guideline => write!(f, "guidelines"),
+            ElementKind::Component => write!(f, "components"),
+        }
+    }
+}
+
+/// Which elements rounding touches; points are always rounded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ElementSelection {
+    pub advance: bool,
+    pub anchors: bool,
+    pub guidelines: bool,
+    pub components: bool,
+}
+
+impl ElementSelection {
+    pub fn includes(&self, kind: ElementKind) -> bool {
+        match kind {
+            ElementKind::Point => true,
+            ElementKind::Advance => self.advance,
+            ElementKind::Anchor => self.anchors,
+            ElementKind::Guideline => self.guidelines,
+            ElementKind::Component => self.components,
+        }
+    }
+}
+
+/// A GLIF element whose coordinates snapping changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementMove {
+    pub kind: ElementKind,
+    /// Changed attributes with their old and new values
+    pub attributes: Vec<(&'static str, String, String)>,
+}
+
+impl fmt::Display for ElementMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let changes: Vec<String> =
+            self.attributes.iter().map(|(attribute, old, new)| format!("{} {} → {}", attribute, old, new)).collect();
+        write!(f, "{} {}", self.kind.element_name(), changes.join(", "))
+    }
+}
+
+/// Outcome of snapping a single `.glif` file
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlifRounding {
+    /// Name of the layer the glyph belongs to
+    pub layer: String,
+    pub path: PathBuf,
+    /// Whether every selected coordinate in the rewritten file is on the grid
+    pub all_on_grid: bool,
+    /// Number of coordinate values changed, per element kind
+    pub changed: BTreeMap<ElementKind, usize>,
+    /// Degenerate contours snapping produced (and could not avoid)
+    pub issues: Vec<TopologyIssue>,
+    /// Number of points moved off their nearest grid position to keep
+    /// contours intact
+    pub nudged: usize,
+    /// Whether the file was left untouched because of `issues`
+    pub skipped: bool,
+    /// Every element snapping moved, in document order
+    pub moves: Vec<ElementMove>,
+    /// The new contents of the file, if snapping changed it
+    pub text: Option<String>,
+}
+
+/// Snaps every point (and any other selected element) in the selected
+/// layers to `grid`, without writing anything; see [`write_snapped`].
+///
+/// Without a safeguard, glyphs whose contours degrade are snapped anyway
+/// and their issues reported.
+pub fn snap_to_grid(
+    ufo_path: &Path,
+    layers: &LayerSelection,
+    elements: &ElementSelection,
+    grid: &Grid,
+    safeguard: Option<Safeguard>
+) -> Result<Vec<GlifRounding>> {
+    let mut results = Vec::new();
+    for layer in layers::select_layers(ufo_path, layers)? {
+        // Entries without a file are reported by `check-contents`
+        for glyph in layers::glyph_files(&layer)?.into_iter().filter(|glyph| glyph.path.is_file()) {
+            let path = glyph.path;
+            let mut glif = GlifSource::parse(fs::read_to_string(&path)?)
+                .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+            let unsnapped = glif.clone();
+            let point_tags = contour_point_tags(&glif);
+            let original = outline_contours(&glif, &point_tags);
+
+            snap_tags(&mut glif, elements, grid);
+
+            let mut snapped = outline_contours(&glif, &point_tags);
+            let mut issues = Vec::new();
+            let mut nudged = 0;
+            for (index, (before, after)) in original.iter().zip(&mut snapped).enumerate() {
+                let found = topology::contour_issues(index, before, &after.points);
+                if found.is_empty() {
+                    continue;
+                }
+                if safeguard == Some(Safeguard::Nudge) {
+                    let nearest = after.points.clone();
+                    if topology::nudge_contour(index, before, &mut after.points, grid) {
+                        nudged += nearest.iter().zip(&after.points).filter(|(a, b)| a != b).count();
+                        continue;
+                    }
+                    after.points = nearest;
+                }
+                issues.extend(found);
+            }
+
+            if safeguard.is_some() && !issues.is_empty() {
+                results.push(GlifRounding {
+                    layer: layer.name.clone(),
+                    path,
+                    all_on_grid: false,
+                    changed: BTreeMap::new(),
+                    issues,
+                    nudged: 0,
+                    skipped: true,
+                    moves: Vec::new(),
+                    text: None,
+                });
+                continue;
+            }
+            if nudged > 0 {
+                set_outline_points(&mut glif, &point_tags, &snapped);
+            }
+
+            // Counted from the final text, so nudged points are included
+            let moves = tag_moves(&unsnapped, &glif);
+            let mut changed = BTreeMap::new();
+            for element_move in &moves {
+                *changed.entry(element_move.kind).or_default() += element_move.attributes.len();
+            }
+            // Files that needed no snapping are left byte-for-byte untouched
+            let text = glif.is_modified().then(|| glif.to_text());
+
+            let all_on_grid = off_grid_coordinates(&glif, elements, grid).is_empty();
+            results.push(GlifRounding {
+                layer: layer.name.clone(),
+                path,
+                all_on_grid,
+                changed,
+                issues,
+                nudged,
+                skipped: false,
+                moves,
+                text,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+/// Saves the files a snapping run changed, replacing the UFO atomically and
+/// keeping a backup. Does nothing if no file changed.
+pub fn write_snapped(ufo_path: &Path, results: &[GlifRounding]) -> Result<()> {
+    if results.iter().all(|result| result.text.is_none()) {
+        return Ok(());
+    }
+    backup::replace_ufo(ufo_path, |staging| {
+        backup::copy_dir(ufo_path, staging)?;
+        for result in results {
+            if let Some(text) = &result.text {
+                fs::write(staging.join(result.path.strip_prefix(ufo_path)?), text)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn snap_tags(glif: &mut GlifSource, elements: &ElementSelection, grid: &Grid) {
+    for index in 0..glif.tags().len() {
+        let Some(kind) = ElementKind::from_element_name(&glif.tags()[index].name).filter(|kind| elements.includes(*kind))
+        else {
+            continue;
+        };
+        for (attribute, axis) in kind.attributes() {
+            let Some(value) = glif.tags()[index].attribute(attribute).and_then(|v| v.parse::<f64>().ok()) else {
+                continue;
+            };
+            let snapped = grid.snap(value, *axis);
+            if snapped != value && !grid.is_on_grid(value, *axis) {
+                glif.set_attribute(index, attribute, &snapped.to_string());
+            }
+        }
+    }
+}
+
+/// Collects the coordinate changes between two versions of the same document
+fn tag_moves(before: &GlifSource, after: &GlifSource) -> Vec<ElementMove> {
+    let mut moves = Vec::new();
+    for (old, new) in before.tags().iter().zip(after.tags()) {
+        let Some(kind) = ElementKind::from_element_name(&new.name) else {
+            continue;
+        };
+        let attributes: Vec<(&'static str, String, String)> = kind
+            .attributes()
+            .iter()
+            .filter_map(|(attribute, _)| {
+                let old = old.attribute(attribute)?;
+                let new = new.attribute(attribute)?;
+                (old != new).then(|| (*attribute, old.to_string(), new.to_string()))
+            })
+            .collect();
+        if !attributes.is_empty() {
+            moves.push(ElementMove { kind, attributes });
+        }
+    }
+    moves
+}
+
+/// Tag indices of the points of each contour in a glyph's `<outline>`
+fn contour_point_tags(glif: &GlifSource) -> Vec<Vec<usize>> {
+    let Some(outline) = glif.children(0, "outline").next() else {
+        return Vec::new();
+    };
+    glif.children(outline, "contour").map(|contour| glif.children(contour, "point").collect()).collect()
+}
+
+/// Reads the contours of a glyph's `<outline>`
+fn outline_contours(glif: &GlifSource, point_tags: &[Vec<usize>]) -> Vec<Contour> {
+    point_tags
+        .iter()
+        .map(|points| {
+            let tags: Vec<_> = points.iter().map(|&index| &glif.tags()[index]).collect();
+            let coordinate = |index: usize, attribute: &str| {
+                tags[index].attribute(attribute).and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0)
+            };
+            Contour {
+                closed: tags.first().and_then(|tag| tag.attribute("type")).is_none_or(|t| t != "move"),
+                points: (0..tags.len())
+                    .map(|index| ContourPoint {
+                        x: coordinate(index, "x"),
+                        y: coordinate(index, "y"),
+                        on_curve: tags[index].attribute("type").is_some_and(|t| t != "offcurve"),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Writes contour point positions back into a glyph's `<outline>`
+fn set_outline_points(glif: &mut GlifSource, point_tags: &[Vec<usize>], contours: &[Contour]) {
+    let current = outline_contours(glif, point_tags);
+    for ((tags, contour), before) in point_tags.iter().zip(contours).zip(&current) {
+        for ((&index, position), old) in tags.iter().zip(&contour.points).zip(&before.points) {
+            if position.x != old.x {
+                glif.set_attribute(index, "x", &position.x.to_string());
+            }
+            if position.y != old.y {
+                glif.set_attribute(index, "y", &position.y.to_string());
+            }
+        }
+    }
+}
+
+/// A coordinate that is not on the grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffGridCoordinate {
+    pub layer: String,
+    pub glyph: String,
+    pub kind: ElementKind,
+    /// Index of the contour, for points
+    pub contour: Option<usize>,
+    /// Index of the point within its contour, or of the element among the
+    /// glyph's elements of the same kind
+    pub index: usize,
+    pub attribute: &'static str,
+    /// The value as written in the file
+    pub value: String,
+}
+
+/// Lists every selected coordinate in the selected layers that is not on
+/// `grid`, in glyph name order
+pub fn check_grid(
+    ufo_path: &Path,
+    layers: &LayerSelection,
+    elements: &ElementSelection,
+    grid: &Grid
+) -> Result<Vec<OffGridCoordinate>> {
+    let mut off_grid = Vec::new();
+    for layer in layers::select_layers(ufo_path, layers)? {
+        for glyph in layers::glyph_files(&layer)?.into_iter().filter(|glyph| glyph.path.is_file()) {
+            let glif = GlifSource::parse(fs::read_to_string(&glyph.path)?)
+                .map_err(|e| anyhow!("{}: {}", glyph.path.display(), e))?;
+            for mut coordinate in off_grid_coordinates(&glif, elements, grid) {
+                coordinate.layer = layer.name.clone();
+                coordinate.glyph = glyph.name.clone();
+                off_grid.push(coordinate);
+            }
+        }
+    }
+    Ok(off_grid)
+}
+
+/// The off-grid coordinates of one document, without layer or glyph names
+fn off_grid_coordinates(glif: &GlifSource, elements: &ElementSelection, grid: &Grid) -> Vec<OffGridCoordinate> {
+    let mut positions: BTreeMap<usize, (Option<usize>, usize)> = BTreeMap::new();
+    for (contour, points) in contour_point_tags(glif).iter().enumerate() {
+        for (point, &tag) in points.iter().enumerate() {
+            positions.insert(tag, (Some(contour), point));
+        }
+    }
+
+    let mut counts: BTreeMap<ElementKind, usize> = BTreeMap::new();
+    let mut off_grid = Vec::new();
+    for (index, tag) in glif.tags().iter().enumerate() {
+        let Some(kind) = ElementKind::from_element_name(&tag.name).filter(|kind| elements.includes(*kind)) else {
+            continue;
+        };
+        let count = counts.entry(kind).or_default();
+        let (contour, element_index) = positions.get(&index).copied().unwrap_or((None, *count));
+        *count += 1;
+
+        for (attribute, axis) in kind.attributes() {
+            let Some(value) = tag.attribute(attribute) else {
+                continue;
+            };
+            if !value.parse::<f64>().is_ok_and(|v| grid.is_on_grid(v, *axis)) {
+                off_grid.push(OffGridCoordinate {
+                    layer: String::new(),
+                    glyph: String::new(),
+                    kind,
+                    contour,
+                    index: element_index,
+                    attribute,
+                    value: value.to_string(),
+                });
+            }
+        }
+    }
+    off_grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapping_never_gives_negative_zero() {
+        assert_eq!(Grid::EVEN.snap(-1.0, Axis::X), -2.0);
+        for value in [-0.4, -0.0, -1e-12] {
+            let snapped = Grid::EVEN.snap(value, Axis::X);
+            assert_eq!(snapped.to_string(), "0", "{}", value);
+            assert!(snapped.is_sign_positive());
+        }
+    }
+
+    #[test]
+    fn fractional_grids_tolerate_float_error() {
+        let grid = Grid::new(0.1, 0.0, 0.0).unwrap();
+        assert_eq!(grid.snap(0.29, Axis::X), 0.3);
+        assert_eq!(grid.snap(0.29, Axis::X).to_string(), "0.3");
+        assert!(grid.is_on_grid(0.3, Axis::X));
+        assert!(grid.is_on_grid(0.1 + 0.2, Axis::X));
+        assert!(!grid.is_on_grid(0.35, Axis::X));
+    }
+
+    #[test]
+    fn offsets_move_positions_but_not_lengths() {
+        let grid = Grid::new(10.0, 3.0, -2.0).unwrap();
+        assert_eq!(grid.snap(7.0, Axis::X), 3.0);
+        assert_eq!(grid.snap(9.0, Axis::X), 13.0);
+        assert_eq!(grid.snap(7.0, Axis::Y), 8.0);
+        assert_eq!(grid.snap(7.0, Axis::Length), 10.0);
+        assert!(grid.is_on_grid(13.0, Axis::X) && !grid.is_on_grid(13.0, Axis::Length));
+    }
+
+    #[test]
+    fn grid_size_must_be_positive() {
+        assert!(Grid::new(0.0, 0.0, 0.0).is_err());
+        assert!(Grid::new(f64::NAN, 0.0, 0.0).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Instance generation by linear interpolation between masters
+//!
+//! Usage:
+//! ```bash
+//! # 35% of the way from Regular to Bold
+//! lilufo interpolate --masters Regular.ufo Bold.ufo --factor 0.35 --out SemiBold.ufo
+//!
+//! # With three masters, 0.0, 0.5 and 1.0 are Light, Regular and Bold
+//! lilufo interpolate --masters Light.ufo Regular.ufo Bold.ufo --factor 0.75 --out SemiBold.ufo
+//! ```
+//!
+//! Masters are spaced evenly between factor 0 and 1; an instance is
+//! interpolated between the two masters on either side of its factor.
+//! Factors outside 0–1 extrapolate from the nearest pair.
+//!
+//! Outlines, advance widths and heights, anchors, component transforms,
+//! kerning and numeric `fontinfo.plist` values are interpolated. Kerning
+//! pairs missing from one master count as zero. Everything else (groups,
+//! features, names, other layers) is taken from the first of the pair.
+
+use std::collections::BTreeSet;
+use anyhow::{anyhow, Result};
+use norad::{Font, Glyph, Name};
+use serde_json::Value;
+use crate::compat::{self, IncompatibilityKind};
+use crate::kerning;
+
+/// `fontinfo.plist` keys that hold codes, dates or versions rather than metrics
+const FONTINFO_EXCLUDED: &[&str] = &[
+    "year",
+    "versionMajor",
+    "versionMinor",
+    "openTypeHeadFlags",
+    "openTypeOS2CodePageRanges",
+    "openTypeOS2FamilyClass",
+    "openTypeOS2Panose",
+    "openTypeOS2Selection",
+    "openTypeOS2Type",
+    "openTypeOS2WidthClass",
+    "openTypeOS2UnicodeRanges",
+    "postscriptUniqueID",
+    "macintoshFONDFamilyID",
+    "postscriptWindowsCharacterSet",
+];
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Interpolates between evenly spaced masters at `factor` (0 is the first
+/// master, 1 the last)
+pub fn interpolate_masters(masters: &[Font], factor: f64) -> Result<Font> {
+    if masters.len() < 2 {
+        return Err(anyhow!("At least two masters are needed to interpolate"));
+    }
+    let segments = (masters.len() - 1) as f64;
+    let position = factor * segments;
+    let segment = (position.floor().max(0.0) as usize).min(masters.len() - 2);
+    interpolate(&masters[segment], &masters[segment + 1], position - segment as f64)
+}
+
+/// Interpolates between two masters; `t` is 0 for `a` and 1 for `b`
+pub fn interpolate(a: &Font, b: &Font, t: f64) -> Result<Font> {
+    let problems: Vec<String> = compat::check_compatibility(&[a, b])
+        .into_iter()
+        .filter(|incompatibility| {
+            !matches!(incompatibility.kind, IncompatibilityKind::GroupMembers | IncompatibilityKind::KerningPair)
+        })
+        .map(|incompatibility| {
+            let location = incompatibility.location.map(|l| format!(" {}", l)).unwrap_or_default();
+            format!(
+                "{}{}: {} ({})",
+                incompatibility.subject,
+                location,
+                incompatibility.kind,
+                incompatibility.values.join(" vs ")
+            )
+        })
+        .collect();
+    if !problems.is_empty() {
+        return Err(anyhow!("Masters are not compatible:\n  {}", problems.join("\n  ")));
+    }
+
+    let mut instance = a.clone();
+    for glyph in instance.default_layer_mut().iter_mut() {
+        // Compatibility was checked above, so every glyph exists in both
+        if let Some(other) = b.default_layer().get_glyph(glyph.name().as_str()) {
+            interpolate_glyph(glyph, other, t);
+        }
+    }
+
+    let pairs: BTreeSet<(String, String)> = [a, b]
+        .iter()
+        .flat_map(|font| kerning::kerning_table(font).pairs.into_iter().map(|pair| (pair.first, pair.second)))
+        .collect();
+    let value = |font: &Font, first: &str, second: &str| {
+        font.kerning.get(first).and_then(|s| s.get(second)).copied().unwrap_or(0.0)
+    };
+    instance.kerning.clear();
+    for (first, second) in pairs {
+        let interpolated = lerp(value(a, &first, &second), value(b, &first, &second), t);
+        instance.kerning.entry(Name::new(&first)?).or_default().insert(Name::new(&second)?, interpolated);
+    }
+
+    instance.font_info = interpolate_font_info(a, b, t)?;
+    Ok(instance)
+}
+
+/// Moves every coordinate of `glyph` towards the matching one in `other`
+fn interpolate_glyph(glyph: &mut Glyph, other: &Glyph, t: f64) {
+    glyph.width = lerp(glyph.width, other.width, t);
+    glyph.height = lerp(glyph.height, other.height, t);
+
+    for (contour, other_contour) in glyph.contours.iter_mut().zip(&other.contours) {
+        for (point, other_point) in contour.points.iter_mut().zip(&other_contour.points) {
+            point.x = lerp(point.x, other_point.x, t);
+            point.y = lerp(point.y, other_point.y, t);
+        }
+    }
+    for (anchor, other_anchor) in glyph.anchors.iter_mut().zip(&other.anchors) {
+        anchor.x = lerp(anchor.x, other_anchor.x, t);
+        anchor.y = lerp(anchor.y, other_anchor.y, t);
+    }
+    for (component, other_component) in glyph.components.iter_mut().zip(&other.components) {
+        let (transform, other_transform) = (&mut component.transform, &other_component.transform);
+        transform.x_scale = lerp(transform.x_scale, other_transform.x_scale, t);
+        transform.xy_scale = lerp(transform.xy_scale, other_transform.xy_scale, t);
+        transform.yx_scale = lerp(transform.yx_scale, other_transform.yx_scale, t);
+        transform.y_scale = lerp(transform.y_scale, other_transform.y_scale, t);
+        transform.x_offset = lerp(transform.x_offset, other_transform.x_offset, t);
+        transform.y_offset = lerp(transform.y_offset, other_transform.y_offset, t);
+    }
+}
+
+/// Interpolates the numeric `fontinfo.plist` values both masters define,
+/// keeping integer fields integral
+fn interpolate_font_info(a: &Font, b: &Font, t: f64) -> Result<norad::FontInfo> {
+    let mut info = serde_json::to_value(&a.font_info)?;
+    let other = serde_json::to_value(&b.font_info)?;
+
+    if let (Value::Object(fields), Value::Object(other_fields)) = (&mut info, &other) {
+        for (key, value) in fields.iter_mut() {
+            if FONTINFO_EXCLUDED.contains(&key.as_str()) {
+                continue;
+            }
+            if let Some(other_value) = other_fields.get(key) {
+                interpolate_value(value, other_value, t);
+            }
+        }
+    }
+
+    serde_json::from_value(info.clone()).map_err(|_| out_of_range_error(a, &info))
+}
+
+/// Names the interpolated field `FontInfo` rejects, such as a negative
+/// `unitsPerEm` or weight class after extrapolating
+fn out_of_range_error(a: &Font, info: &Value) -> anyhow::Error {
+    let Ok(Value::Object(original)) = serde_json::to_value(&a.font_info) else {
+        return anyhow!("Interpolated fontinfo.plist values are invalid");
+    };
+    let Value::Object(fields) = info else {
+        return anyhow!("Interpolated fontinfo.plist values are invalid");
+    };
+    for (key, value) in fields {
+        if original.get(key) == Some(value) {
+            continue;
+        }
+        let mut single = original.clone();
+        single.insert(key.clone(), value.clone());
+        if serde_json::from_value::<norad::FontInfo>(Value::Object(single)).is_err() {
+            return anyhow!("Interpolated fontinfo.plist value {} = {} is out of range", key, value);
+        }
+    }
+    anyhow!("Interpolated fontinfo.plist values are invalid")
+}
+
+fn interpolate_value(value: &mut Value, other: &Value, t: f64) {
+    match (&mut *value, other) {
+        (Value::Number(number), Value::Number(other_number)) => {
+            let (Some(a), Some(b)) = (number.as_f64(), other_number.as_f64()) else {
+                return;
+            };
+            let interpolated = lerp(a, b, t);
+            *value = if number.is_f64() || other_number.is_f64() {
+                serde_json::json!(interpolated)
+            } else {
+                serde_json::json!(interpolated.round() as i64)
+            };
+        }
+        // Blue zones, stem snaps and the like
+        (Value::Array(items), Value::Array(other_items)) if items.len() == other_items.len() => {
+            for (item, other_item) in items.iter_mut().zip(other_items) {
+                interpolate_value(item, other_item, t);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use norad::{Contour, ContourPoint, PointType};
+    use norad::fontinfo::Os2WidthClass;
+
+    fn master(x: f64, weight_class: u32, kern: f64) -> Font {
+        let mut font = Font::new();
+        let mut glyph = Glyph::new("A");
+        glyph.width = 500.0 + x;
+        let point = |x: f64, y: f64| ContourPoint::new(x, y, PointType::Line, false, None, None, None);
+        glyph.contours.push(Contour::new(vec![point(0.0, 0.0), point(x, 0.0), point(x, 700.0)], None, None));
+        font.default_layer_mut().insert_glyph(glyph);
+        font.kerning.entry(Name::new("A").unwrap()).or_default().insert(Name::new("A").unwrap(), kern);
+        font.font_info.open_type_os2_weight_class = Some(weight_class);
+        font.font_info.ascender = Some(700.0);
+        font
+    }
+
+    #[test]
+    fn interpolates_outlines_kerning_and_fontinfo() {
+        let instance = interpolate(&master(100.0, 100, -20.0), &master(300.0, 900, -60.0), 0.25).unwrap();
+        let glyph = instance.default_layer().get_glyph("A").unwrap();
+        assert_eq!(glyph.width, 650.0);
+        assert_eq!(glyph.contours[0].points[1].x, 150.0);
+        assert_eq!(instance.kerning["A"]["A"], -30.0);
+        assert_eq!(instance.font_info.open_type_os2_weight_class, Some(300));
+        assert_eq!(instance.font_info.ascender, Some(700.0));
+    }
+
+    #[test]
+    fn codes_and_dates_come_from_the_first_master() {
+        let mut light = master(100.0, 100, 0.0);
+        let mut bold = master(300.0, 900, 0.0);
+        (light.font_info.year, bold.font_info.year) = (Some(2010), Some(2020));
+        light.font_info.open_type_os2_width_class = Some(Os2WidthClass::Normal);
+        bold.font_info.open_type_os2_width_class = Some(Os2WidthClass::UltraExpanded);
+
+        let instance = interpolate(&light, &bold, 0.5).unwrap();
+        assert_eq!(instance.font_info.year, Some(2010));
+        assert_eq!(instance.font_info.open_type_os2_width_class, Some(Os2WidthClass::Normal));
+        assert_eq!(instance.font_info.open_type_os2_weight_class, Some(500));
+    }
+
+    #[test]
+    fn masters_are_spaced_evenly() {
+        let masters = [master(0.0, 100, 0.0), master(100.0, 400, 0.0), master(300.0, 900, 0.0)];
+        let instance = interpolate_masters(&masters, 0.75).unwrap();
+        assert_eq!(instance.default_layer().get_glyph("A").unwrap().contours[0].points[1].x, 200.0);
+    }
+
+    #[test]
+    fn extrapolating_below_zero_names_the_field() {
+        let error = interpolate(&master(100.0, 100, 0.0), &master(300.0, 900, 0.0), -1.0).unwrap_err();
+        assert!(error.to_string().contains("openTypeOS2WeightClass"), "{}", error);
+    }
+
+    #[test]
+    fn incompatible_masters_are_rejected() {
+        let mut other = master(300.0, 900, 0.0);
+        other.default_layer_mut().get_glyph_mut("A").unwrap().contours.clear();
+        assert!(interpolate(&master(100.0, 100, 0.0), &other, 0.5).is_err());
+    }
+}
@@ -1,224 +1,482 @@
 //! Kerning tools for UFO fonts
-//! 
+//!
 //! Usage:
 //! ```bash
 //! # Display all kerning groups in a UFO
-//! lilufo --ufo-path font.ufo --show-kerning-groups
-//! 
+//! lilufo --ufo-path font.ufo show-kerning-groups
+//!
 //! # Display all kerning pairs in a UFO
-//! lilufo --ufo-path font.ufo --show-kerning
-//! 
+//! lilufo --ufo-path font.ufo show-kerning
+//!
 //! # Add a new kerning group
-//! lilufo --ufo-path font.ufo \
-//!        --add-kerning-group \
-//!        --group-name "O_group" \
-//!        --group-side "left" \
-//!        --group-members "O,Q,C,G"
+//! lilufo --ufo-path font.ufo add-kerning-group \
+//!        --name "O_group" \
+//!        --side "left" \
+//!        --members "O,Q,C,G"
 //!
 //! # Edit an existing kerning group (replaces existing members)
-//! lilufo --ufo-path font.ufo \
-//!        --edit-kerning-group \
-//!        --group-name "O_group" \
-//!        --group-side "left" \
-//!        --group-members "O,Q,C,G,Ø"
+//! lilufo --ufo-path font.ufo edit-kerning-group \
+//!        --name "O_group" \
+//!        --side "left" \
+//!        --members "O,Q,C,G,Ø"
 //!
 //! # Append members to an existing kerning group
-//! lilufo --ufo-path font.ufo \
-//!        --edit-kerning-group \
-//!        --append-members \
-//!        --group-name "O_group" \
-//!        --group-side "left" \
-//!        --group-members "Ø"
+//! lilufo --ufo-path font.ufo edit-kerning-group \
+//!        --append \
+//!        --name "O_group" \
+//!        --side "left" \
+//!        --members "Ø"
 //!
 //! # Add a new kerning pair (using group names)
-//! lilufo --ufo-path font.ufo \
-//!        --add-kerning-pair \
+//! lilufo --ufo-path font.ufo add-kerning-pair \
 //!        --first "@O_group" \
 //!        --second "@A_group" \
 //!        --value -50
 //!
 //! # Add a new kerning pair (using glyph names)
-//! lilufo --ufo-path font.ufo \
-//!        --add-kerning-pair \
+//! lilufo --ufo-path font.ufo add-kerning-pair \
 //!        --first "T" \
 //!        --second "a" \
 //!        --value -100
 //! ```
 //!
 //! Note: For both add and edit commands:
-//! - group-side must be either "left" or "right"
-//! - group-members should be a comma-separated list of glyph names
-//! - group-name should not include the "public.kern1." or "public.kern2." prefix
-//! - use --append-members with --edit-kerning-group to add to existing members instead of replacing them
+//! - side must be either "left" or "right"
+//! - members should be a comma-separated list of glyph names
+//! - name should not include the "public.kern1." or "public.kern2." prefix
+//! - use --append with edit-kerning-group to add to existing members instead of replacing them
+//!
+//! The functions in this module operate on an in-memory [`Font`]; saving the
+//! result is left to the caller.
 
-use std::path::{Path, PathBuf};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::collections::btree_map::Entry;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
 use anyhow::Result;
-use plist::Value;
-use std::fs;
-use norad::{Font, Name};
+use norad::{Font, Groups, Name};
+
+pub const LEFT_GROUP_PREFIX: &str = "public.kern1.";
+pub const RIGHT_GROUP_PREFIX: &str = "public.kern2.";
+
+/// Which side of a kerning pair a group applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GroupSide {
+    /// First side of a pair (`public.kern1.`)
+    Left,
+    /// Second side of a pair (`public.kern2.`)
+    Right,
+}
+
+impl GroupSide {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            GroupSide::Left => LEFT_GROUP_PREFIX,
+            GroupSide::Right => RIGHT_GROUP_PREFIX,
+        }
+    }
+
+    /// Returns the full UFO group name, e.g. `public.kern1.O` for `O`
+    pub fn full_name(self, group_name: &str) -> String {
+        format!("{}{}", self.prefix(), group_name)
+    }
+
+    /// Splits a full UFO group name into its side and short name
+    pub fn split_full_name(full_name: &str) -> Option<(GroupSide, &str)> {
+        if let Some(name) = full_name.strip_prefix(LEFT_GROUP_PREFIX) {
+            Some((GroupSide::Left, name))
+        } else {
+            full_name.strip_prefix(RIGHT_GROUP_PREFIX).map(|name| (GroupSide::Right, name))
+        }
+    }
+}
+
+impl FromStr for GroupSide {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "left" => Ok(GroupSide::Left),
+            "right" => Ok(GroupSide::Right),
+            _ => Err(anyhow::anyhow!("group_side must be either 'left' or 'right'")),
+        }
+    }
+}
+
+impl fmt::Display for GroupSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupSide::Left => write!(f, "left"),
+            GroupSide::Right => write!(f, "right"),
+        }
+    }
+}
+
+/// A single `public.kern1.`/`public.kern2.` group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KerningGroup {
+    /// Group name without the `public.kern*.` prefix
+    pub name: String,
+    pub side: GroupSide,
+    pub members: Vec<String>,
+}
+
+impl KerningGroup {
+    pub fn full_name(&self) -> String {
+        self.side.full_name(&self.name)
+    }
+}
+
+/// All kerning groups of a font, left groups first, each side sorted by name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupSet {
+    pub groups: Vec<KerningGroup>,
+}
+
+impl GroupSet {
+    pub fn iter(&self) -> impl Iterator<Item = &KerningGroup> {
+        self.groups.iter()
+    }
+
+    pub fn side(&self, side: GroupSide) -> impl Iterator<Item = &KerningGroup> {
+        self.groups.iter().filter(move |group| group.side == side)
+    }
+
+    pub fn get(&self, side: GroupSide, name: &str) -> Option<&KerningGroup> {
+        self.groups.iter().find(|group| group.side == side && group.name == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+/// A single kerning entry, keyed the way it is stored in `kerning.plist`
+#[derive(Debug, Clone, PartialEq)]
+pub struct KerningPair {
+    pub first: String,
+    pub second: String,
+    pub value: f64,
+}
+
+impl KerningPair {
+    /// First side in `@group` notation
+    pub fn first_display(&self) -> String {
+        display_name(&self.first)
+    }
+
+    /// Second side in `@group` notation
+    pub fn second_display(&self) -> String {
+        display_name(&self.second)
+    }
+}
+
+/// All kerning pairs of a font, sorted by first then second key
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KerningTable {
+    pub pairs: Vec<KerningPair>,
+}
+
+impl KerningTable {
+    pub fn iter(&self) -> impl Iterator<Item = &KerningPair> {
+        self.pairs.iter()
+    }
+
+    pub fn get(&self, first: &str, second: &str) -> Option<f64> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.first == first && pair.second == second)
+            .map(|pair| pair.value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
 
-pub fn display_kerning_groups(ufo_path: &Path) -> Result<()> {
+/// Converts a kerning key to `@group` notation, leaving glyph names untouched
+pub fn display_name(key: &str) -> String {
+    match GroupSide::split_full_name(key) {
+        Some((_, name)) => format!("@{}", name),
+        None => key.to_string(),
+    }
+}
+
+/// Converts `@group` notation to the full `public.kern*.` key for `side`
+pub fn kerning_key(name: &str, side: GroupSide) -> String {
+    match name.strip_prefix('@') {
+        Some(group_name) => side.full_name(group_name),
+        None => name.to_string(),
+    }
+}
+
+pub fn kerning_groups(font: &Font) -> GroupSet {
+    let mut groups: Vec<KerningGroup> = font
+        .groups
+        .iter()
+        .filter_map(|(full_name, members)| {
+            GroupSide::split_full_name(full_name).map(|(side, name)| KerningGroup {
+                name: name.to_string(),
+                side,
+                members: members.iter().map(|m| m.to_string()).collect(),
+            })
+        })
+        .collect();
+    groups.sort_by(|a, b| (a.side, &a.name).cmp(&(b.side, &b.name)));
+
+    GroupSet { groups }
+}
+
+pub fn kerning_table(font: &Font) -> KerningTable {
+    let pairs = font
+        .kerning
+        .iter()
+        .flat_map(|(first, seconds)| {
+            seconds.iter().map(move |(second, value)| KerningPair {
+                first: first.to_string(),
+                second: second.to_string(),
+                value: *value,
+            })
+        })
+        .collect();
+
+    KerningTable { pairs }
+}
+
+/// A glyph that belongs to more than one kerning group on the same side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupConflict {
+    pub glyph: String,
+    pub side: GroupSide,
+    /// Short names of every group on `side` that contains the glyph
+    pub groups: Vec<String>,
+}
+
+/// How group writes treat members that are already grouped or missing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemberPolicy {
+    /// Take members out of other groups on the same side instead of failing
+    pub move_members: bool,
+    /// Accept members that are not glyphs in the default layer
+    pub allow_missing: bool,
+}
+
+/// A glyph taken from its previous group by a `move_members` write
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovedMember {
+    pub glyph: String,
+    /// Short name of the group the glyph was removed from
+    pub from_group: String,
+}
+
+/// Reads `groups.plist` directly, without the validation `Font::load`
+/// performs, so fonts with conflicting groups can still be inspected
+pub fn load_groups(ufo_path: &Path) -> Result<Groups> {
     let groups_path = ufo_path.join("groups.plist");
     if !groups_path.exists() {
-        println!("No groups.plist found in UFO");
-        return Ok(());
-    }
-
-    let groups_data = fs::read_to_string(groups_path)?;
-    let groups: BTreeMap<String, Value> = plist::from_bytes(groups_data.as_bytes())?;
-
-    println!("Kerning Groups:");
-    println!("---------------");
-    
-    // Sort and display left groups
-    println!("\nLeft Groups (prefix: public.kern1):");
-    for (name, members) in groups.iter() {
-        if name.starts_with("public.kern1.") {
-            let group_name = name.strip_prefix("public.kern1.").unwrap_or(name);
-            if let Value::Array(members) = members {
-                let members_str: Vec<String> = members
-                    .iter()
-                    .filter_map(|m| {
-                        if let Value::String(glyph) = m {
-                            Some(glyph.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-                println!("@{} → {}", group_name, members_str.join(", "));
-            }
-        }
+        return Ok(Groups::new());
     }
+    Ok(plist::from_file(groups_path)?)
+}
 
-    // Sort and display right groups
-    println!("\nRight Groups (prefix: public.kern2):");
-    for (name, members) in groups.iter() {
-        if name.starts_with("public.kern2.") {
-            let group_name = name.strip_prefix("public.kern2.").unwrap_or(name);
-            if let Value::Array(members) = members {
-                let members_str: Vec<String> = members
-                    .iter()
-                    .filter_map(|m| {
-                        if let Value::String(glyph) = m {
-                            Some(glyph.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-                println!("@{} → {}", group_name, members_str.join(", "));
+/// Finds every glyph that is a member of more than one `public.kern1.` or
+/// more than one `public.kern2.` group, which UFO 3 does not allow
+pub fn find_group_conflicts(groups: &Groups) -> Vec<GroupConflict> {
+    let mut memberships: BTreeMap<(GroupSide, String), Vec<String>> = BTreeMap::new();
+    for (full_name, members) in groups {
+        let Some((side, name)) = GroupSide::split_full_name(full_name) else {
+            continue;
+        };
+        for member in members {
+            let groups = memberships.entry((side, member.to_string())).or_default();
+            if !groups.iter().any(|g| g == name) {
+                groups.push(name.to_string());
             }
         }
     }
 
-    Ok(())
+    memberships
+        .into_iter()
+        .filter(|(_, groups)| groups.len() > 1)
+        .map(|((side, glyph), groups)| GroupConflict { glyph, side, groups })
+        .collect()
 }
 
-pub fn display_kerning(ufo_path: &Path) -> Result<()> {
-    let kerning_path = ufo_path.join("kerning.plist");
-    if !kerning_path.exists() {
-        println!("No kerning.plist found in UFO");
-        return Ok(());
+/// Returns up to three glyph names in the default layer that are within a
+/// small edit distance of `name`, closest first.
+///
+/// Distances are measured case-insensitively with the usual look-alikes
+/// (`0`/`O`, `1`/`l`) folded together, so a typo like "0" suggests "O"
+/// rather than every other single-letter glyph.
+pub fn suggest_glyph_names(font: &Font, name: &str) -> Vec<String> {
+    fn fold(name: &str) -> String {
+        name.chars()
+            .map(|c| match c {
+                '0' => 'o',
+                '1' => 'l',
+                c => c.to_ascii_lowercase(),
+            })
+            .collect()
     }
 
-    let kerning_data = fs::read_to_string(kerning_path)?;
-    let kerning: BTreeMap<String, Value> = plist::from_bytes(kerning_data.as_bytes())?;
+    let folded_name = fold(name);
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut candidates: Vec<(usize, usize, &str)> = font
+        .default_layer()
+        .iter()
+        .map(|glyph| {
+            let glyph_name = glyph.name().as_str();
+            let folded = strsim::levenshtein(&folded_name, &fold(glyph_name));
+            (folded, strsim::levenshtein(name, glyph_name), glyph_name)
+        })
+        .filter(|(folded, raw, _)| *folded == 0 || *raw <= max_distance)
+        .collect();
+    candidates.sort();
 
-    println!("Kerning Pairs:");
-    println!("--------------");
+    let best = candidates.first().map(|(folded, _, _)| *folded);
+    candidates
+        .into_iter()
+        .take_while(|(folded, _, _)| Some(*folded) == best)
+        .take(3)
+        .map(|(_, _, glyph)| glyph.to_string())
+        .collect()
+}
 
-    for (first, second_dict) in kerning.iter() {
-        if let Value::Dictionary(pairs) = second_dict {
-            let first_name = if first.starts_with("public.kern1.") {
-                format!("@{}", first.strip_prefix("public.kern1.").unwrap_or(first))
+/// Fails if any of `names` is not a glyph in the default layer, suggesting
+/// close matches for each missing name
+pub fn check_glyphs_exist<S: AsRef<str>>(font: &Font, names: &[S]) -> Result<()> {
+    let layer = font.default_layer();
+    let missing: Vec<String> = names
+        .iter()
+        .map(|name| name.as_ref())
+        .filter(|name| !layer.contains_glyph(name))
+        .map(|name| {
+            let suggestions = suggest_glyph_names(font, name);
+            if suggestions.is_empty() {
+                format!("'{}'", name)
             } else {
-                first.clone()
-            };
-
-            for (second, value) in pairs {
-                let second_name = if second.starts_with("public.kern2.") {
-                    format!("@{}", second.strip_prefix("public.kern2.").unwrap_or(second))
-                } else {
-                    second.clone()
-                };
+                format!("'{}' (did you mean '{}'?)", name, suggestions.join("', '"))
+            }
+        })
+        .collect();
 
-                if let Value::Integer(kern_value) = value {
-                    println!("{} {} → {}", first_name, second_name, kern_value);
-                }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Glyphs not found in the default layer: {}", missing.join(", ")))
+    }
+}
+
+/// Checks that `members` are not already in another group on `group_side`.
+///
+/// With `move_members`, conflicting glyphs are removed from their old groups
+/// instead of rejecting the write.
+fn claim_members(
+    font: &mut Font,
+    group_name: &str,
+    group_side: GroupSide,
+    members: &[Name],
+    move_members: bool
+) -> Result<Vec<MovedMember>> {
+    let mut conflicts = Vec::new();
+    for group in kerning_groups(font).side(group_side) {
+        if group.name == group_name {
+            continue;
+        }
+        for member in members {
+            if group.members.iter().any(|m| m == member.as_str()) {
+                conflicts.push(MovedMember {
+                    glyph: member.to_string(),
+                    from_group: group.name.clone(),
+                });
             }
         }
     }
 
-    Ok(())
+    if conflicts.is_empty() {
+        return Ok(conflicts);
+    }
+    if !move_members {
+        let listing: Vec<String> = conflicts
+            .iter()
+            .map(|c| format!("'{}' (in @{})", c.glyph, c.from_group))
+            .collect();
+        return Err(anyhow::anyhow!(
+            "Glyphs already belong to another {} kerning group: {}",
+            group_side,
+            listing.join(", ")
+        ));
+    }
+
+    for conflict in &conflicts {
+        let full_name = group_side.full_name(&conflict.from_group);
+        if let Some(old_members) = font.groups.get_mut(full_name.as_str()) {
+            old_members.retain(|m| m.as_str() != conflict.glyph);
+        }
+    }
+    Ok(conflicts)
 }
 
 pub fn add_kerning_group(
-    ufo_path: &PathBuf,
+    font: &mut Font,
     group_name: &str,
-    group_side: &str,
-    members: &[String]
-) -> Result<()> {
-    let mut font = Font::load(ufo_path)?;
-    
-    // Validate group side
-    if group_side != "left" && group_side != "right" {
-        return Err(anyhow::anyhow!("group_side must be either 'left' or 'right'"));
-    }
-
-    // Directly borrow the groups as mutable
-    let groups = &mut font.groups;
-    
-    // Add the new group
-    let prefix = if group_side == "left" { "public.kern1." } else { "public.kern2." };
-    let full_group_name = format!("{}{}", prefix, group_name);
-    
+    group_side: GroupSide,
+    members: &[String],
+    policy: MemberPolicy
+) -> Result<Vec<MovedMember>> {
+    let full_group_name = group_side.full_name(group_name);
+
     // Convert Vec<String> to Vec<Name>
     let name_members: Vec<norad::Name> = members
         .iter()
         .map(|s| norad::Name::new(s))
         .collect::<Result<Vec<_>, _>>()?;
-    
-    groups.insert(Name::new(&full_group_name)?, name_members);
-    
-    // Save the changes back to the UFO file
-    font.save(ufo_path)?;
-    
-    println!("Successfully added kerning group '{}'", group_name);
-    Ok(())
+
+    if !policy.allow_missing {
+        check_glyphs_exist(font, &name_members)?;
+    }
+    let moved = claim_members(font, group_name, group_side, &name_members, policy.move_members)?;
+    font.groups.insert(Name::new(&full_group_name)?, name_members);
+    Ok(moved)
 }
 
 pub fn edit_kerning_group(
-    ufo_path: &PathBuf,
+    font: &mut Font,
     group_name: &str,
-    group_side: &str,
+    group_side: GroupSide,
     members: &[String],
-    append: bool
-) -> Result<()> {
-    let mut font = Font::load(ufo_path)?;
-    
-    // Validate group side
-    if group_side != "left" && group_side != "right" {
-        return Err(anyhow::anyhow!("group_side must be either 'left' or 'right'"));
-    }
-
-    // Get the full group name with prefix
-    let prefix = if group_side == "left" { "public.kern1." } else { "public.kern2." };
-    let full_group_name = format!("{}{}", prefix, group_name);
-    let full_name = Name::new(&full_group_name)?;
-    
+    append: bool,
+    policy: MemberPolicy
+) -> Result<Vec<MovedMember>> {
+    let full_name = Name::new(&group_side.full_name(group_name))?;
+
     // Check if the group exists
     if !font.groups.contains_key(&full_name) {
         return Err(anyhow::anyhow!("Kerning group '{}' does not exist", group_name));
     }
-    
+
     // Convert new members to Vec<Name>
     let mut name_members: Vec<norad::Name> = members
         .iter()
         .map(|s| norad::Name::new(s))
         .collect::<Result<Vec<_>, _>>()?;
-    
+
+    // Only the names given are checked, so members already in the group
+    // never block an append
+    if !policy.allow_missing {
+        check_glyphs_exist(font, &name_members)?;
+    }
+    let moved = claim_members(font, group_name, group_side, &name_members, policy.move_members)?;
+
     // If appending, combine with existing members
     if append {
         if let Some(existing_members) = font.groups.get(&full_name) {
@@ -230,65 +488,490 @@ pub fn edit_kerning_group(
             name_members = combined_members;
         }
     }
-    
-    // Update the group
+
     font.groups.insert(full_name, name_members);
-    
-    // Save the changes back to the UFO file
-    font.save(ufo_path)?;
-    
-    println!("Successfully updated kerning group '{}'", group_name);
-    Ok(())
+    Ok(moved)
 }
 
 pub fn add_kerning_pair(
-    ufo_path: &PathBuf,
+    font: &mut Font,
     first: &str,
     second: &str,
-    value: i32
+    value: f64,
+    allow_missing: bool
 ) -> Result<()> {
-    let mut font = Font::load(ufo_path)?;
-    
-    // Validate that if groups are used, they exist and have correct prefixes
-    if first.starts_with("@") {
-        let group_name = format!("public.kern1.{}", &first[1..]);
-        if !font.groups.contains_key(&Name::new(&group_name)?) {
-            return Err(anyhow::anyhow!("First group '{}' does not exist", first));
-        }
+    // Convert @GroupName notation to public.kern*.GroupName
+    let first_key = kerning_key(first, GroupSide::Left);
+    let second_key = kerning_key(second, GroupSide::Right);
+
+    // Validate that if groups are used, they exist
+    if first.starts_with('@') && !font.groups.contains_key(first_key.as_str()) {
+        return Err(anyhow::anyhow!("First group '{}' does not exist", first));
+    }
+    if second.starts_with('@') && !font.groups.contains_key(second_key.as_str()) {
+        return Err(anyhow::anyhow!("Second group '{}' does not exist", second));
     }
-    
-    if second.starts_with("@") {
-        let group_name = format!("public.kern2.{}", &second[1..]);
-        if !font.groups.contains_key(&Name::new(&group_name)?) {
-            return Err(anyhow::anyhow!("Second group '{}' does not exist", second));
+
+    if !allow_missing {
+        let glyphs: Vec<&str> = [first, second]
+            .into_iter()
+            .filter(|name| !name.starts_with('@') && GroupSide::split_full_name(name).is_none())
+            .collect();
+        check_glyphs_exist(font, &glyphs)?;
+    }
+
+    // Add or update the kerning pair
+    font.kerning
+        .entry(Name::new(&first_key)?)
+        .or_default()
+        .insert(Name::new(&second_key)?, value);
+    Ok(())
+}
+
+/// What to do with kerning entries that reference a group being removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferencePolicy {
+    /// Delete every pair that references the group
+    Drop,
+    /// Replace every pair that references the group with one pair per member,
+    /// keeping any existing glyph-level pair for that member
+    Flatten,
+}
+
+impl FromStr for ReferencePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "drop" => Ok(ReferencePolicy::Drop),
+            "flatten" => Ok(ReferencePolicy::Flatten),
+            _ => Err(anyhow::anyhow!("references must be either 'drop' or 'flatten'")),
         }
     }
-    
-    // Convert @GroupName notation to public.kern*.GroupName
-    let first_key = if first.starts_with("@") {
-        format!("public.kern1.{}", &first[1..])
-    } else {
-        first.to_string()
+}
+
+/// Kerning changes made while removing a group
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupRemoval {
+    /// The members the group had before it was removed
+    pub members: Vec<String>,
+    /// Pairs that referenced the group and were removed
+    pub removed_pairs: Vec<KerningPair>,
+    /// Glyph-level pairs added when flattening
+    pub added_pairs: Vec<KerningPair>,
+}
+
+/// Removes a single kerning pair, returning its value
+pub fn remove_kerning_pair(font: &mut Font, first: &str, second: &str) -> Result<f64> {
+    let first_key = kerning_key(first, GroupSide::Left);
+    let second_key = kerning_key(second, GroupSide::Right);
+
+    let seconds = font
+        .kerning
+        .get_mut(first_key.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Kerning pair '{}' '{}' does not exist", first, second))?;
+    let value = seconds
+        .remove(second_key.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Kerning pair '{}' '{}' does not exist", first, second))?;
+
+    if seconds.is_empty() {
+        font.kerning.remove(first_key.as_str());
+    }
+    Ok(value)
+}
+
+/// Removes a kerning group.
+///
+/// If kerning still references the group, `references` decides whether those
+/// pairs are dropped or flattened; without a policy the removal is refused so
+/// the font never references a missing group.
+pub fn remove_kerning_group(
+    font: &mut Font,
+    group_name: &str,
+    group_side: GroupSide,
+    references: Option<ReferencePolicy>
+) -> Result<GroupRemoval> {
+    let full_group_name = group_side.full_name(group_name);
+    let members = font
+        .groups
+        .get(full_group_name.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Kerning group '{}' does not exist", group_name))?
+        .clone();
+
+    let referencing: Vec<KerningPair> = kerning_table(font)
+        .pairs
+        .into_iter()
+        .filter(|pair| match group_side {
+            GroupSide::Left => pair.first == full_group_name,
+            GroupSide::Right => pair.second == full_group_name,
+        })
+        .collect();
+
+    let policy = match references {
+        Some(policy) => policy,
+        None if referencing.is_empty() => ReferencePolicy::Drop,
+        None => {
+            return Err(anyhow::anyhow!(
+                "Kerning group '{}' is referenced by {} kerning pair(s); choose whether to drop or flatten them",
+                group_name,
+                referencing.len()
+            ))
+        }
     };
-    
-    let second_key = if second.starts_with("@") {
-        format!("public.kern2.{}", &second[1..])
-    } else {
-        second.to_string()
+
+    for pair in &referencing {
+        remove_kerning_pair(font, &pair.first, &pair.second)?;
+    }
+
+    let mut added_pairs = Vec::new();
+    if policy == ReferencePolicy::Flatten {
+        for pair in &referencing {
+            for member in &members {
+                let (first, second) = match group_side {
+                    GroupSide::Left => (member.to_string(), pair.second.clone()),
+                    GroupSide::Right => (pair.first.clone(), member.to_string()),
+                };
+                let seconds = font.kerning.entry(Name::new(&first)?).or_default();
+                if let Entry::Vacant(entry) = seconds.entry(Name::new(&second)?) {
+                    entry.insert(pair.value);
+                    added_pairs.push(KerningPair { first, second, value: pair.value });
+                }
+            }
+        }
+    }
+
+    font.groups.remove(full_group_name.as_str());
+
+    Ok(GroupRemoval {
+        members: members.iter().map(|m| m.to_string()).collect(),
+        removed_pairs: referencing,
+        added_pairs,
+    })
+}
+
+/// A kerning value changed by [`round_kerning`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct KerningChange {
+    pub first: String,
+    pub second: String,
+    pub old_value: f64,
+    pub new_value: f64,
+}
+
+/// Rounds every kerning value to the nearest integer, returning the pairs
+/// whose value changed
+pub fn round_kerning(font: &mut Font) -> Vec<KerningChange> {
+    let mut changes = Vec::new();
+    for (first, seconds) in font.kerning.iter_mut() {
+        for (second, value) in seconds.iter_mut() {
+            let rounded = value.round();
+            if rounded != *value {
+                changes.push(KerningChange {
+                    first: first.to_string(),
+                    second: second.to_string(),
+                    old_value: *value,
+                    new_value: rounded,
+                });
+                *value = rounded;
+            }
+        }
+    }
+    changes
+}
+
+/// Maps each glyph to the kerning group it belongs to on each side
+struct GroupIndex<'a> {
+    groups: BTreeMap<(GroupSide, &'a str), &'a str>,
+}
+
+impl<'a> GroupIndex<'a> {
+    fn new(font: &'a Font) -> Self {
+        let mut groups = BTreeMap::new();
+        for (full_name, members) in &font.groups {
+            if let Some((side, _)) = GroupSide::split_full_name(full_name) {
+                for member in members {
+                    groups.entry((side, member.as_str())).or_insert(full_name.as_str());
+                }
+            }
+        }
+        GroupIndex { groups }
+    }
+
+    fn group_of(&self, glyph: &str, side: GroupSide) -> Option<&'a str> {
+        self.groups.get(&(side, glyph)).copied()
+    }
+}
+
+/// The kind of kerning entry that matched a glyph pair, in precedence order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KerningSource {
+    GlyphGlyph,
+    GlyphGroup,
+    GroupGlyph,
+    GroupGroup,
+}
+
+impl fmt::Display for KerningSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KerningSource::GlyphGlyph => write!(f, "glyph-glyph"),
+            KerningSource::GlyphGroup => write!(f, "glyph-group"),
+            KerningSource::GroupGlyph => write!(f, "group-glyph"),
+            KerningSource::GroupGroup => write!(f, "group-group"),
+        }
+    }
+}
+
+/// The effective kerning between two glyphs and how it was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct KerningResolution {
+    pub first: String,
+    pub second: String,
+    /// Full name of the `public.kern1.` group containing `first`
+    pub first_group: Option<String>,
+    /// Full name of the `public.kern2.` group containing `second`
+    pub second_group: Option<String>,
+    /// Every kerning entry that applies to the pair, highest precedence first
+    pub matches: Vec<(KerningSource, KerningPair)>,
+}
+
+impl KerningResolution {
+    /// The entry that determines the value, if any applies
+    pub fn winner(&self) -> Option<&(KerningSource, KerningPair)> {
+        self.matches.first()
+    }
+
+    /// The effective kerning value; pairs without any entry kern by zero
+    pub fn value(&self) -> f64 {
+        self.winner().map(|(_, pair)| pair.value).unwrap_or(0.0)
+    }
+
+    /// Whether the winning entry is an exception overriding a less specific one
+    pub fn is_exception(&self) -> bool {
+        self.matches.len() > 1
+    }
+}
+
+/// Looks up the effective kerning between two glyphs, following the UFO
+/// precedence: glyph-glyph, then glyph-group, then group-glyph, then
+/// group-group
+fn resolve_glyph_pair(font: &Font, index: &GroupIndex, first: &str, second: &str) -> KerningResolution {
+    let first_group = index.group_of(first, GroupSide::Left);
+    let second_group = index.group_of(second, GroupSide::Right);
+
+    let candidates = [
+        (KerningSource::GlyphGlyph, Some(first), Some(second)),
+        (KerningSource::GlyphGroup, Some(first), second_group),
+        (KerningSource::GroupGlyph, first_group, Some(second)),
+        (KerningSource::GroupGroup, first_group, second_group),
+    ];
+    let matches = candidates
+        .into_iter()
+        .filter_map(|(source, first_key, second_key)| {
+            let (first_key, second_key) = first_key.zip(second_key)?;
+            let value = *font.kerning.get(first_key)?.get(second_key)?;
+            Some((source, KerningPair {
+                first: first_key.to_string(),
+                second: second_key.to_string(),
+                value,
+            }))
+        })
+        .collect();
+
+    KerningResolution {
+        first: first.to_string(),
+        second: second.to_string(),
+        first_group: first_group.map(str::to_string),
+        second_group: second_group.map(str::to_string),
+        matches,
+    }
+}
+
+/// Resolves the effective kerning between two glyphs, taking group
+/// membership and exceptions into account
+pub fn lookup_kerning(font: &Font, first: &str, second: &str) -> KerningResolution {
+    resolve_glyph_pair(font, &GroupIndex::new(font), first, second)
+}
+
+/// Expands every `public.kern1.`/`public.kern2.` reference in the font's
+/// kerning into glyph-to-glyph pairs.
+///
+/// Each glyph pair gets the value the UFO precedence rules resolve to, so
+/// exceptions keep overriding their group pairs. Pairs that resolve to zero
+/// are left out since they only existed to cancel group kerning.
+pub fn flatten_kerning(font: &Font) -> KerningTable {
+    let expand = |key: &str| -> Vec<String> {
+        if GroupSide::split_full_name(key).is_some() {
+            font.groups
+                .get(key)
+                .map(|members| members.iter().map(|m| m.to_string()).collect())
+                .unwrap_or_default()
+        } else {
+            vec![key.to_string()]
+        }
     };
-    
-    // Add or update the kerning pair
-    if let Some(first_dict) = font.kerning.get_mut(&Name::new(&first_key)?) {
-        first_dict.insert(Name::new(&second_key)?, value as f64);
-    } else {
-        let mut first_dict = BTreeMap::new();
-        first_dict.insert(Name::new(&second_key)?, value as f64);
-        font.kerning.insert(Name::new(&first_key)?, first_dict);
-    }
-    
-    // Save the changes back to the UFO file
-    font.save(ufo_path)?;
-    
-    println!("Successfully added kerning pair '{}' '{}' → {}", first, second, value);
+
+    let mut glyph_pairs = BTreeSet::new();
+    for (first, seconds) in &font.kerning {
+        let firsts = expand(first);
+        for second in seconds.keys() {
+            let seconds = expand(second);
+            for first_glyph in &firsts {
+                for second_glyph in &seconds {
+                    glyph_pairs.insert((first_glyph.clone(), second_glyph.clone()));
+                }
+            }
+        }
+    }
+
+    let index = GroupIndex::new(font);
+    let pairs = glyph_pairs
+        .into_iter()
+        .filter_map(|(first, second)| {
+            let value = resolve_glyph_pair(font, &index, &first, &second).value();
+            (value != 0.0).then_some(KerningPair { first, second, value })
+        })
+        .collect();
+
+    KerningTable { pairs }
+}
+
+/// Replaces the font's kerning with the pairs in `table`
+pub fn replace_kerning(font: &mut Font, table: &KerningTable) -> Result<()> {
+    font.kerning.clear();
+    for pair in table.iter() {
+        font.kerning
+            .entry(Name::new(&pair.first)?)
+            .or_default()
+            .insert(Name::new(&pair.second)?, pair.value);
+    }
     Ok(())
 }
+
+/// Fonts for the unit tests of the kerning modules
+#[cfg(test)]
+pub(crate) mod test_support {
+    use norad::{Font, Glyph, Name};
+
+    /// A font with empty glyphs, groups and kerning pairs, all given by name
+    pub(crate) fn font(glyphs: &[&str], groups: &[(&str, &[&str])], pairs: &[(&str, &str, f64)]) -> Font {
+        let mut font = Font::new();
+        for name in glyphs {
+            font.default_layer_mut().insert_glyph(Glyph::new(name));
+        }
+        for (name, members) in groups {
+            font.groups.insert(Name::new(name).unwrap(), members.iter().map(|m| Name::new(m).unwrap()).collect());
+        }
+        for (first, second, value) in pairs {
+            font.kerning.entry(Name::new(first).unwrap()).or_default().insert(Name::new(second).unwrap(), *value);
+        }
+        font
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::test_support::font;
+
+    fn members(font: &Font, group: &str) -> Vec<String> {
+        font.groups[group].iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn append_only_checks_the_new_members() {
+        // `stale` is not a glyph but is already in the group
+        let mut font = font(&["O", "Q"], &[("public.kern1.O", &["O", "stale"])], &[]);
+        edit_kerning_group(&mut font, "O", GroupSide::Left, &["Q".into()], true, MemberPolicy::default()).unwrap();
+        assert_eq!(members(&font, "public.kern1.O"), ["O", "Q", "stale"]);
+
+        let error = edit_kerning_group(&mut font, "O", GroupSide::Left, &["Z".into()], true, MemberPolicy::default())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'Z'") && !error.contains("stale"), "{}", error);
+    }
+
+    fn kerned_font() -> Font {
+        font(
+            &["T", "V", "o", "e", "a"],
+            &[("public.kern1.T", &["T", "V"]), ("public.kern2.o", &["o", "e"])],
+            &[
+                ("public.kern1.T", "public.kern2.o", -50.0),
+                ("public.kern1.T", "e", -30.0),
+                ("V", "public.kern2.o", -20.0),
+                ("V", "e", 0.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn removing_a_referenced_group_needs_a_policy() {
+        let mut font = kerned_font();
+        assert!(remove_kerning_group(&mut font, "o", GroupSide::Right, None).is_err());
+        assert!(font.groups.contains_key("public.kern2.o"));
+
+        let removal = remove_kerning_group(&mut font, "o", GroupSide::Right, Some(ReferencePolicy::Drop)).unwrap();
+        assert_eq!(removal.removed_pairs.len(), 2);
+        assert!(kerning_table(&font).iter().all(|pair| pair.second != "public.kern2.o"));
+    }
+
+    #[test]
+    fn flattening_a_removed_group_keeps_existing_glyph_pairs() {
+        let mut font = kerned_font();
+        let removal = remove_kerning_group(&mut font, "o", GroupSide::Right, Some(ReferencePolicy::Flatten)).unwrap();
+        assert!(!font.groups.contains_key("public.kern2.o"));
+        // `T e` and `V e` already had their own pairs, so only `o` is added
+        let table = kerning_table(&font);
+        assert_eq!(table.get("public.kern1.T", "o"), Some(-50.0));
+        assert_eq!(table.get("public.kern1.T", "e"), Some(-30.0));
+        assert_eq!(table.get("V", "o"), Some(-20.0));
+        assert_eq!(table.get("V", "e"), Some(0.0));
+        assert_eq!(removal.added_pairs.len(), 2);
+    }
+
+    #[test]
+    fn pairs_use_group_keys_for_their_side() {
+        let mut font = kerned_font();
+        add_kerning_pair(&mut font, "@T", "a", -5.0, false).unwrap();
+        assert_eq!(font.kerning["public.kern1.T"]["a"], -5.0);
+        assert!(add_kerning_pair(&mut font, "T", "x", -5.0, false).is_err());
+        assert_eq!(remove_kerning_pair(&mut font, "@T", "a").unwrap(), -5.0);
+        assert!(remove_kerning_pair(&mut font, "@T", "a").is_err());
+    }
+
+    #[test]
+    fn groups_cannot_share_members_unless_moved() {
+        let mut font = kerned_font();
+        let error = add_kerning_group(&mut font, "V", GroupSide::Left, &["V".into()], MemberPolicy::default());
+        assert!(error.is_err());
+
+        let policy = MemberPolicy { move_members: true, ..Default::default() };
+        let moved = add_kerning_group(&mut font, "V", GroupSide::Left, &["V".into()], policy).unwrap();
+        assert_eq!(moved, [MovedMember { glyph: "V".into(), from_group: "T".into() }]);
+        assert_eq!(members(&font, "public.kern1.T"), ["T"]);
+        assert_eq!(members(&font, "public.kern1.V"), ["V"]);
+    }
+
+    #[test]
+    fn flattening_keeps_exceptions_and_drops_zero_pairs() {
+        let table = flatten_kerning(&kerned_font());
+        let pairs: Vec<(&str, &str, f64)> =
+            table.iter().map(|pair| (pair.first.as_str(), pair.second.as_str(), pair.value)).collect();
+        assert_eq!(pairs, [("T", "e", -30.0), ("T", "o", -50.0), ("V", "o", -20.0)]);
+    }
+
+    #[test]
+    fn lookup_follows_ufo_precedence() {
+        let font = kerned_font();
+        let resolve = |first, second| {
+            let resolution = lookup_kerning(&font, first, second);
+            (resolution.winner().map(|(source, _)| *source), resolution.value())
+        };
+        assert_eq!(resolve("T", "o"), (Some(KerningSource::GroupGroup), -50.0));
+        assert_eq!(resolve("T", "e"), (Some(KerningSource::GroupGlyph), -30.0));
+        assert_eq!(resolve("V", "o"), (Some(KerningSource::GlyphGroup), -20.0));
+        assert_eq!(resolve("V", "e"), (Some(KerningSource::GlyphGlyph), 0.0));
+        assert_eq!(resolve("a", "o"), (None, 0.0));
+        assert!(lookup_kerning(&font, "V", "e").is_exception());
+    }
+}
@@ -0,0 +1,332 @@
+//! Kerning import and export for spreadsheets and other UFOs
+//!
+//! Usage:
+//! ```bash
+//! # Export pairs as CSV, or as a left-by-right matrix
+//! lilufo --ufo-path font.ufo --format csv export-kerning --output kerning.csv
+//! lilufo --ufo-path font.ufo --format matrix export-kerning
+//!
+//! # Import pairs from a CSV/TSV file (first,second,value with @group notation)
+//! lilufo --ufo-path font.ufo import-kerning --from kerning.csv
+//!
+//! # Import the kerning of another master, keeping values already set here
+//! lilufo --ufo-path font.ufo import-kerning --from other.ufo --strategy keep-existing
+//! ```
+//!
+//! A `first,second,value` header row is optional. Fields may be quoted the
+//! way CSV quotes them, as `export-kerning` does. Files ending in `.tsv` or
+//! `.tab` (or whose first line contains a tab) are read as tab-separated,
+//! everything else as comma-separated.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::Result;
+use norad::{Font, Name};
+use crate::kerning::{self, GroupSide, KerningChange, KerningPair, KerningTable};
+
+/// How imported values combine with pairs that already exist in the font
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Replace existing values with imported ones
+    Overwrite,
+    /// Only insert pairs the font does not have yet
+    KeepExisting,
+    /// Add imported values to existing ones
+    Add,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "overwrite" => Ok(MergeStrategy::Overwrite),
+            "keep-existing" => Ok(MergeStrategy::KeepExisting),
+            "add" => Ok(MergeStrategy::Add),
+            _ => Err(anyhow::anyhow!("strategy must be one of 'overwrite', 'keep-existing' or 'add'")),
+        }
+    }
+}
+
+/// A pair that was not imported, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedPair {
+    pub pair: KerningPair,
+    pub reason: String,
+}
+
+/// What an import did to the font's kerning
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportSummary {
+    pub inserted: Vec<KerningPair>,
+    pub changed: Vec<KerningChange>,
+    pub skipped: Vec<SkippedPair>,
+}
+
+/// Column names of the optional header row
+const HEADER: [&str; 3] = ["first", "second", "value"];
+
+/// Parses `first,second,value` rows into a kerning table, converting
+/// `@group` notation to full `public.kern*.` keys
+pub fn parse_kerning_table(text: &str, delimiter: char) -> Result<KerningTable> {
+    let mut pairs = Vec::new();
+    let mut first_row = true;
+
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let is_first_row = std::mem::replace(&mut first_row, false);
+        let fields = split_record(line, delimiter).map_err(|e| anyhow::anyhow!("Line {}: {}", index + 1, e))?;
+        if fields.len() < 3 {
+            return Err(anyhow::anyhow!("Line {}: expected three columns (first, second, value)", index + 1));
+        }
+        if is_first_row && fields.iter().zip(HEADER).all(|(field, name)| field.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+
+        let value = fields[2]
+            .parse::<f64>()
+            .map_err(|_| anyhow::anyhow!("Line {}: invalid kerning value '{}'", index + 1, fields[2]))?;
+
+        pairs.push(KerningPair {
+            first: kerning::kerning_key(&fields[0], GroupSide::Left),
+            second: kerning::kerning_key(&fields[1], GroupSide::Right),
+            value,
+        });
+    }
+
+    Ok(KerningTable { pairs })
+}
+
+/// Splits one row into fields. A field that starts with `"` may contain the
+/// delimiter, with `""` standing for a literal quote.
+fn split_record(line: &str, delimiter: char) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|&c| c != delimiter && c.is_whitespace()).is_some() {}
+        let mut field = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err(anyhow::anyhow!("unterminated quoted field")),
+                }
+            }
+            while chars.next_if(|&c| c != delimiter && c.is_whitespace()).is_some() {}
+            if chars.peek().is_some_and(|&c| c != delimiter) {
+                return Err(anyhow::anyhow!("unexpected text after quoted field '{}'", field));
+            }
+        } else {
+            while let Some(c) = chars.next_if(|&c| c != delimiter) {
+                field.push(c);
+            }
+            field = field.trim().to_string();
+        }
+        fields.push(field);
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+/// Reads a CSV or TSV kerning file
+pub fn read_kerning_file(path: &Path) -> Result<KerningTable> {
+    let text = fs::read_to_string(path)?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    let first_line = text.lines().next().unwrap_or("");
+    let delimiter = if extension == "tsv" || extension == "tab" || first_line.contains('\t') { '\t' } else { ',' };
+    parse_kerning_table(&text, delimiter)
+}
+
+/// Reads kerning from a CSV/TSV file, or from a UFO if `path` is one
+pub fn read_kerning_source(path: &Path) -> Result<KerningTable> {
+    if path.is_dir() {
+        Ok(kerning::kerning_table(&Font::load(path)?))
+    } else {
+        read_kerning_file(path)
+    }
+}
+
+/// Merges `table` into the font's kerning.
+///
+/// Pairs that reference groups the font does not have are skipped, as are
+/// pairs naming glyphs outside the default layer unless `allow_missing`.
+pub fn import_kerning(
+    font: &mut Font,
+    table: &KerningTable,
+    strategy: MergeStrategy,
+    allow_missing: bool
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+
+    for pair in table.iter() {
+        if let Some(reason) = missing_reference(font, pair, allow_missing) {
+            summary.skipped.push(SkippedPair { pair: pair.clone(), reason });
+            continue;
+        }
+
+        let existing = font.kerning.get(pair.first.as_str()).and_then(|s| s.get(pair.second.as_str())).copied();
+        let new_value = match (existing, strategy) {
+            (None, _) => {
+                summary.inserted.push(pair.clone());
+                pair.value
+            }
+            (Some(old_value), MergeStrategy::KeepExisting) => {
+                summary.skipped.push(SkippedPair {
+                    pair: pair.clone(),
+                    reason: format!("kept existing value {}", old_value),
+                });
+                continue;
+            }
+            (Some(old_value), strategy) => {
+                let new_value = if strategy == MergeStrategy::Add { old_value + pair.value } else { pair.value };
+                if new_value == old_value {
+                    summary.skipped.push(SkippedPair { pair: pair.clone(), reason: "unchanged".to_string() });
+                    continue;
+                }
+                summary.changed.push(KerningChange {
+                    first: pair.first.clone(),
+                    second: pair.second.clone(),
+                    old_value,
+                    new_value,
+                });
+                new_value
+            }
+        };
+
+        font.kerning
+            .entry(Name::new(&pair.first)?)
+            .or_default()
+            .insert(Name::new(&pair.second)?, new_value);
+    }
+
+    Ok(summary)
+}
+
+fn missing_reference(font: &Font, pair: &KerningPair, allow_missing: bool) -> Option<String> {
+    for key in [&pair.first, &pair.second] {
+        if GroupSide::split_full_name(key).is_some() {
+            if !font.groups.contains_key(key.as_str()) {
+                return Some(format!("group '{}' does not exist", kerning::display_name(key)));
+            }
+        } else if !allow_missing && !font.default_layer().contains_glyph(key) {
+            return Some(format!("glyph '{}' is not in the default layer", key));
+        }
+    }
+    None
+}
+
+/// Kerning arranged as a table of first sides (rows) by second sides
+/// (columns), groups before glyphs on each axis
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KerningMatrix {
+    /// Kerning keys of the rows
+    pub rows: Vec<String>,
+    /// Kerning keys of the columns
+    pub columns: Vec<String>,
+    values: BTreeMap<(usize, usize), f64>,
+}
+
+impl KerningMatrix {
+    /// The value at a row and column, if that pair is kerned
+    pub fn get(&self, row: usize, column: usize) -> Option<f64> {
+        self.values.get(&(row, column)).copied()
+    }
+}
+
+/// Arranges a kerning table as a matrix
+pub fn kerning_matrix(table: &KerningTable) -> KerningMatrix {
+    fn axis<'a>(keys: impl Iterator<Item = &'a String>) -> Vec<String> {
+        let mut keys: Vec<String> = keys.cloned().collect();
+        keys.sort_by_key(|key| (GroupSide::split_full_name(key).is_none(), key.clone()));
+        keys.dedup();
+        keys
+    }
+
+    let rows = axis(table.iter().map(|pair| &pair.first));
+    let columns = axis(table.iter().map(|pair| &pair.second));
+    let values = table
+        .iter()
+        .filter_map(|pair| {
+            let row = rows.iter().position(|key| *key == pair.first)?;
+            let column = columns.iter().position(|key| *key == pair.second)?;
+            Some(((row, column), pair.value))
+        })
+        .collect();
+
+    KerningMatrix { rows, columns, values }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kerning::test_support::font;
+
+    fn pairs(table: &KerningTable) -> Vec<(&str, &str, f64)> {
+        table.iter().map(|pair| (pair.first.as_str(), pair.second.as_str(), pair.value)).collect()
+    }
+
+    #[test]
+    fn reads_quoted_csv_fields() {
+        let table = parse_kerning_table("first,second,value\na,\"b,c\",3\n\"x\"\"y\", z ,-1.5\n", ',').unwrap();
+        assert_eq!(pairs(&table), [("a", "b,c", 3.0), ("x\"y", "z", -1.5)]);
+    }
+
+    #[test]
+    fn a_quoted_only_row_is_not_mistaken_for_a_header() {
+        let table = parse_kerning_table("a,\"b,c\",3\n", ',').unwrap();
+        assert_eq!(pairs(&table), [("a", "b,c", 3.0)]);
+    }
+
+    #[test]
+    fn an_unparsable_first_row_is_an_error() {
+        let error = parse_kerning_table("a,b,oops\nc,d,1\n", ',').unwrap_err();
+        assert_eq!(error.to_string(), "Line 1: invalid kerning value 'oops'");
+        assert!(parse_kerning_table("a,\"b,3\n", ',').is_err());
+        assert!(parse_kerning_table("a,b\n", ',').is_err());
+    }
+
+    #[test]
+    fn groups_use_the_side_of_their_column() {
+        let table = parse_kerning_table("@O\t@O\t-20\n", '\t').unwrap();
+        assert_eq!(pairs(&table), [("public.kern1.O", "public.kern2.O", -20.0)]);
+    }
+
+    #[test]
+    fn exported_csv_reads_back() {
+        // Quoted the way `export-kerning --format csv` quotes fields
+        let csv = "first,second,value\n\"a,b\",@O,-10\nT,\"q\"\"\",12.5\n";
+        let table = parse_kerning_table(csv, ',').unwrap();
+        assert_eq!(pairs(&table), [("a,b", "public.kern2.O", -10.0), ("T", "q\"", 12.5)]);
+    }
+
+    fn font_with_kerning(pairs: &[(&str, &str, f64)]) -> Font {
+        font(&["A", "V", "T", "o"], &[], pairs)
+    }
+
+    #[test]
+    fn strategies_combine_existing_values() {
+        let table = parse_kerning_table("A,V,-10\nT,o,-5\nA,x,1\n", ',').unwrap();
+        let value = |font: &Font, first: &str, second: &str| font.kerning[first][second];
+
+        let mut font = font_with_kerning(&[("A", "V", -30.0)]);
+        let summary = import_kerning(&mut font, &table, MergeStrategy::Overwrite, false).unwrap();
+        assert_eq!((summary.inserted.len(), summary.changed.len(), summary.skipped.len()), (1, 1, 1));
+        assert_eq!(value(&font, "A", "V"), -10.0);
+
+        let mut font = font_with_kerning(&[("A", "V", -30.0)]);
+        import_kerning(&mut font, &table, MergeStrategy::KeepExisting, false).unwrap();
+        assert_eq!(value(&font, "A", "V"), -30.0);
+
+        let mut font = font_with_kerning(&[("A", "V", -30.0)]);
+        import_kerning(&mut font, &table, MergeStrategy::Add, true).unwrap();
+        assert_eq!(value(&font, "A", "V"), -40.0);
+        assert_eq!(value(&font, "A", "x"), 1.0);
+    }
+}
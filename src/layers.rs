@@ -0,0 +1,192 @@
+//! Layer discovery for tools that work on the glyph files directly
+//!
+//! UFO 3 lists every layer and its directory in `layercontents.plist`; the
+//! default layer is always stored in `glyphs`. UFO 2 fonts have no
+//! `layercontents.plist` and only the `glyphs` directory.
+//!
+//! Each layer's `contents.plist` maps glyph names to file names. It is the
+//! authority on which `.glif` files belong to the font: stray files in a
+//! layer directory are ignored, and file names need not follow the usual
+//! naming scheme. Entries must stay inside the UFO: absolute paths and
+//! `..` components are rejected.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+use anyhow::Result;
+use glob::{glob, Pattern};
+
+/// Directory of the default layer
+pub const DEFAULT_LAYER_DIR: &str = "glyphs";
+
+/// A layer as listed in `layercontents.plist`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerDir {
+    pub name: String,
+    /// Path of the layer directory inside the UFO
+    pub path: PathBuf,
+}
+
+impl LayerDir {
+    pub fn is_default(&self) -> bool {
+        self.path.file_name().is_some_and(|name| name == DEFAULT_LAYER_DIR)
+    }
+}
+
+/// Which layers a glyph rewriting tool works on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LayerSelection {
+    /// Only the default layer (`glyphs`)
+    #[default]
+    Default,
+    /// A single layer, by name
+    Named(String),
+    /// Every layer listed in `layercontents.plist`
+    All,
+}
+
+/// Checks that a path listed in a plist stays inside the directory it is
+/// relative to
+fn contained_path<'a>(entry: &'a str, plist_path: &Path) -> Result<&'a Path> {
+    let path = Path::new(entry);
+    let contained = !entry.is_empty()
+        && path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !contained {
+        return Err(anyhow::anyhow!("{} refers to '{}', which is outside the UFO", plist_path.display(), entry));
+    }
+    Ok(path)
+}
+
+/// Lists the layers of a UFO in `layercontents.plist` order
+pub fn layer_dirs(ufo_path: &Path) -> Result<Vec<LayerDir>> {
+    let layer_contents_path = ufo_path.join("layercontents.plist");
+    if !layer_contents_path.exists() {
+        return Ok(vec![LayerDir {
+            name: "public.default".to_string(),
+            path: ufo_path.join(DEFAULT_LAYER_DIR),
+        }]);
+    }
+
+    let entries: Vec<Vec<String>> = plist::from_file(&layer_contents_path)?;
+    entries
+        .into_iter()
+        .map(|entry| match entry.as_slice() {
+            [name, dir] => Ok(LayerDir {
+                name: name.clone(),
+                path: ufo_path.join(contained_path(dir, &layer_contents_path)?),
+            }),
+            _ => Err(anyhow::anyhow!("Invalid entry in {}", layer_contents_path.display())),
+        })
+        .collect()
+}
+
+/// Resolves a layer selection against the layers of a UFO
+pub fn select_layers(ufo_path: &Path, selection: &LayerSelection) -> Result<Vec<LayerDir>> {
+    let layers = layer_dirs(ufo_path)?;
+    match selection {
+        LayerSelection::All => Ok(layers),
+        LayerSelection::Default => layers
+            .into_iter()
+            .find(LayerDir::is_default)
+            .map(|layer| vec![layer])
+            .ok_or_else(|| anyhow::anyhow!("No default layer found in {}", ufo_path.display())),
+        LayerSelection::Named(name) => {
+            let names: Vec<String> = layers.iter().map(|layer| layer.name.clone()).collect();
+            layers
+                .into_iter()
+                .find(|layer| layer.name == *name)
+                .map(|layer| vec![layer])
+                .ok_or_else(|| anyhow::anyhow!("Layer '{}' does not exist (available: {})", name, names.join(", ")))
+        }
+    }
+}
+
+/// A glyph and the file it is stored in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlyphFile {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Lists a layer's glyphs from its `contents.plist`, in glyph name order
+pub fn glyph_files(layer: &LayerDir) -> Result<Vec<GlyphFile>> {
+    let contents_path = layer.path.join("contents.plist");
+    let contents: BTreeMap<String, String> = plist::from_file(&contents_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", contents_path.display(), e))?;
+    contents
+        .into_iter()
+        .map(|(name, file)| {
+            let path = layer.path.join(contained_path(&file, &contents_path)?);
+            Ok(GlyphFile { name, path })
+        })
+        .collect()
+}
+
+/// Mismatches between a layer's `contents.plist` and its directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentsReport {
+    pub layer: String,
+    /// `.glif` files that no `contents.plist` entry refers to
+    pub orphans: Vec<PathBuf>,
+    /// Entries whose file does not exist
+    pub missing: Vec<GlyphFile>,
+}
+
+impl ContentsReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphans.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Compares every layer's `contents.plist` with the files on disk
+pub fn check_contents(ufo_path: &Path) -> Result<Vec<ContentsReport>> {
+    let mut reports = Vec::new();
+    for layer in layer_dirs(ufo_path)? {
+        let glyphs = glyph_files(&layer)?;
+        let listed: BTreeSet<&Path> = glyphs.iter().map(|glyph| glyph.path.as_path()).collect();
+
+        let layer_path = layer.path.to_str().ok_or(anyhow::anyhow!("Invalid UFO path"))?;
+        let pattern = format!("{}/**/*.glif", Pattern::escape(layer_path));
+        let mut orphans = Vec::new();
+        for entry in glob(&pattern)? {
+            let path = entry?;
+            if !listed.contains(path.as_path()) {
+                orphans.push(path);
+            }
+        }
+
+        let missing = glyphs.iter().filter(|glyph| !glyph.path.is_file()).cloned().collect();
+        reports.push(ContentsReport { layer: layer.name, orphans, missing });
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn entries_must_stay_inside_the_ufo() {
+        let plist = Path::new("font.ufo/glyphs/contents.plist");
+        for entry in ["../x.glif", "a/../../x.glif", "/abs.glif", ""] {
+            let error = contained_path(entry, plist).unwrap_err();
+            assert!(error.to_string().contains("outside the UFO"), "{}", entry);
+        }
+        assert_eq!(contained_path("sub/dir/a.glif", plist).unwrap(), Path::new("sub/dir/a.glif"));
+        assert_eq!(contained_path("./a.glif", plist).unwrap(), Path::new("./a.glif"));
+    }
+
+    #[test]
+    fn layer_directories_outside_the_ufo_are_refused() {
+        let ufo = std::env::temp_dir().join(format!("lilufo-layers-{}.ufo", std::process::id()));
+        fs::create_dir_all(&ufo).unwrap();
+        let layers = |dir: &str| vec![vec!["public.default".to_string(), dir.to_string()]];
+
+        plist::to_file_xml(ufo.join("layercontents.plist"), &layers("../glyphs")).unwrap();
+        assert!(layer_dirs(&ufo).is_err());
+
+        plist::to_file_xml(ufo.join("layercontents.plist"), &layers("glyphs")).unwrap();
+        assert_eq!(layer_dirs(&ufo).unwrap()[0].path, ufo.join("glyphs"));
+        fs::remove_dir_all(&ufo).unwrap();
+    }
+}
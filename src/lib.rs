@@ -0,0 +1,42 @@
+//! Lil' UFO - UFO font file manipulation library
+//!
+//! The `lilufo` binary is a thin presentation layer over the functions in
+//! this crate, which operate on loaded [`norad::Font`]s (or, for the glyph
+//! rewriting tools, on a UFO path) and return plain data structures instead
+//! of printing.
+//!
+//! ```no_run
+//! use lilufo::kerning::{self, GroupSide};
+//! use norad::Font;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut font = Font::load("MyFont.ufo")?;
+//! kerning::add_kerning_group(&mut font, "O", GroupSide::Left, &["O".into(), "Q".into()], Default::default())?;
+//! kerning::add_kerning_pair(&mut font, "@O", "T", -80.0, false)?;
+//!
+//! for pair in kerning::kerning_table(&font).iter() {
+//!     println!("{} {} {}", pair.first, pair.second, pair.value);
+//! }
+//! font.save("MyFont.ufo")?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod backup;
+pub mod basic;
+pub mod batch;
+pub mod compat;
+pub mod designspace;
+pub mod diff;
+pub mod fea;
+pub mod glif;
+pub mod grid;
+pub mod interpolate;
+pub mod kerning;
+pub mod kerning_io;
+pub mod layers;
+pub mod sync;
+pub mod topology;
+
+pub use basic::{font_summary, FontSummary};
+pub use kerning::{GroupSet, GroupSide, KerningGroup, KerningPair, KerningTable};
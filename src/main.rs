@@ -1,19 +1,43 @@
 // main.rs
-use std::path::PathBuf;        // For handling file paths
+use std::path::{Path, PathBuf}; // For handling file paths
+use std::str::FromStr;
 use clap::{Parser, Subcommand}; // For parsing command-line arguments
+use clap::builder::{PossibleValuesParser, TypedValueParser};
 use norad::Font;               // UFO font handling library
 use anyhow::Result;            // For error handling
+use lilufo::GroupSide;         // Types from the lilufo library crate (lib.rs)
+use lilufo::kerning::{MemberPolicy, ReferencePolicy};
+use lilufo::kerning_io::MergeStrategy;
+use lilufo::grid::{ElementSelection, Grid};
+use lilufo::layers::LayerSelection;
+use lilufo::topology::Safeguard;
 
-mod basic;                     // Imports a separate module named "basic" (defined in basic.rs)
-mod kerning;
-mod commands;                  // Import our new commands module
+mod commands;                  // Presentation layer for each subcommand
+
+use commands::OutputFormat;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Path to the UFO file
+    /// Path to the UFO file, or a quoted glob pattern; repeat to process several UFOs in parallel
     #[arg(short, long)]
-    ufo_path: Option<PathBuf>,
+    ufo_path: Vec<String>,
+
+    /// Path to a designspace; commands then run on each of its source UFOs (repeatable)
+    #[arg(long)]
+    designspace: Vec<PathBuf>,
+
+    /// The single UFO a batch runs this process on
+    #[arg(long, hide = true)]
+    batch_font: Option<PathBuf>,
+
+    /// Output format for the reporting commands (matrix only applies to kerning pairs)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Show what a modifying command would change without writing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
 
     #[command(subcommand)]
     command: Option<Commands>,
@@ -25,7 +49,109 @@ enum Commands {
     BasicInfo {},
     
     /// Round all points to nearest even integer
-    RoundToEven {},
+    RoundToEven {
+        /// Round only this layer (default: the default layer)
+        #[arg(long, conflicts_with = "all_layers")]
+        layer: Option<String>,
+
+        /// Round every layer listed in layercontents.plist
+        #[arg(long)]
+        all_layers: bool,
+
+        /// Also round advance widths and heights
+        #[arg(long)]
+        advance: bool,
+
+        /// Also round anchor positions
+        #[arg(long)]
+        anchors: bool,
+
+        /// Also round glyph guideline positions
+        #[arg(long)]
+        guidelines: bool,
+
+        /// Also round component offsets
+        #[arg(long)]
+        components: bool,
+
+        /// Protect contours from degenerate rounding
+        #[arg(long, value_parser = choice::<Safeguard>(&["nudge", "skip"]))]
+        safeguard: Option<Safeguard>,
+    },
+
+    /// Snap all points to a grid of the given size
+    SnapToGrid {
+        /// Grid size in font units
+        #[arg(long)]
+        grid: f64,
+
+        /// Grid origin as x,y (positions only; advances and component offsets snap to multiples of the grid)
+        #[arg(long, allow_hyphen_values = true)]
+        offset: Option<String>,
+
+        /// Snap only this layer (default: the default layer)
+        #[arg(long, conflicts_with = "all_layers")]
+        layer: Option<String>,
+
+        /// Snap every layer listed in layercontents.plist
+        #[arg(long)]
+        all_layers: bool,
+
+        /// Also snap advance widths and heights
+        #[arg(long)]
+        advance: bool,
+
+        /// Also snap anchor positions
+        #[arg(long)]
+        anchors: bool,
+
+        /// Also snap glyph guideline positions
+        #[arg(long)]
+        guidelines: bool,
+
+        /// Also snap component offsets
+        #[arg(long)]
+        components: bool,
+
+        /// Protect contours from degenerate snapping
+        #[arg(long, value_parser = choice::<Safeguard>(&["nudge", "skip"]))]
+        safeguard: Option<Safeguard>,
+    },
+    
+    /// List every coordinate that is off the grid and exit non-zero if any are
+    CheckGrid {
+        /// Grid size in font units
+        #[arg(long)]
+        grid: f64,
+
+        /// Grid origin as x,y (positions only; advances and component offsets are checked against multiples of the grid)
+        #[arg(long, allow_hyphen_values = true)]
+        offset: Option<String>,
+
+        /// Check only this layer (default: the default layer)
+        #[arg(long, conflicts_with = "all_layers")]
+        layer: Option<String>,
+
+        /// Check every layer listed in layercontents.plist
+        #[arg(long)]
+        all_layers: bool,
+
+        /// Also check advance widths and heights
+        #[arg(long)]
+        advance: bool,
+
+        /// Also check anchor positions
+        #[arg(long)]
+        anchors: bool,
+
+        /// Also check glyph guideline positions
+        #[arg(long)]
+        guidelines: bool,
+
+        /// Also check component offsets
+        #[arg(long)]
+        components: bool,
+    },
     
     /// Display kerning groups
     ShowKerningGroups {},
@@ -46,6 +172,14 @@ enum Commands {
         /// Members of the kerning group (comma-separated)
         #[arg(long)]
         members: String,
+
+        /// Move members out of other groups on the same side instead of failing
+        #[arg(long = "move")]
+        move_members: bool,
+
+        /// Allow members that are not glyphs in the default layer
+        #[arg(long)]
+        allow_missing: bool,
     },
     
     /// Edit an existing kerning group
@@ -65,6 +199,14 @@ enum Commands {
         /// Append members to existing group instead of replacing them
         #[arg(long)]
         append: bool,
+
+        /// Move members out of other groups on the same side instead of failing
+        #[arg(long = "move")]
+        move_members: bool,
+
+        /// Allow members that are not glyphs in the default layer
+        #[arg(long)]
+        allow_missing: bool,
     },
     
     /// Add a new kerning pair
@@ -77,72 +219,249 @@ enum Commands {
         #[arg(long)]
         second: String,
         
-        /// Kerning value (integer)
+        /// Kerning value (may be negative or fractional)
+        #[arg(long, allow_hyphen_values = true)]
+        value: f64,
+
+        /// Allow glyph names that are not in the default layer
+        #[arg(long)]
+        allow_missing: bool,
+    },
+
+    /// Remove a kerning pair
+    RemoveKerningPair {
+        /// First member of kerning pair (glyph or group name)
+        #[arg(long)]
+        first: String,
+
+        /// Second member of kerning pair (glyph or group name)
+        #[arg(long)]
+        second: String,
+    },
+
+    /// Remove a kerning group
+    RemoveKerningGroup {
+        /// Name of the kerning group
+        #[arg(long)]
+        name: String,
+
+        /// Side of the kerning group (left or right)
+        #[arg(long)]
+        side: String,
+
+        /// What to do with kerning pairs that reference the group
+        #[arg(long, value_parser = choice::<ReferencePolicy>(&["drop", "flatten"]))]
+        references: Option<ReferencePolicy>,
+    },
+
+    /// Round all kerning values to integers
+    RoundKerning {},
+
+    /// Report glyphs that belong to more than one kerning group per side
+    CheckKerningGroups {},
+
+    /// Expand group kerning into glyph-to-glyph pairs
+    FlattenKerning {
+        /// Replace the font's kerning with the flattened pairs
+        #[arg(long)]
+        write: bool,
+
+        /// Write the flattened pair list to a file (in the chosen --format)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export groups and kerning as OpenType feature syntax
+    ExportFea {
+        /// Write the feature code to a file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Merge the feature code into the UFO's features.fea between marker comments
+        #[arg(long)]
+        merge: bool,
+    },
+
+    /// Import kerning from a CSV/TSV file or another UFO
+    ImportKerning {
+        /// CSV/TSV file (first,second,value) or UFO to import from
+        #[arg(long)]
+        from: PathBuf,
+
+        /// How to treat pairs that already exist
+        #[arg(
+            long,
+            default_value = "overwrite",
+            value_parser = choice::<MergeStrategy>(&["overwrite", "keep-existing", "add"])
+        )]
+        strategy: MergeStrategy,
+
+        /// Allow glyph names that are not in the default layer
+        #[arg(long)]
+        allow_missing: bool,
+    },
+
+    /// Export kerning as a pair list or matrix (csv, tsv, json or matrix via --format)
+    ExportKerning {
+        /// Write to a file instead of stdout (text and matrices are written as CSV, or TSV for .tsv files)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Report .glif files missing from contents.plist and entries without a file
+    CheckContents {},
+
+    /// Restore the UFO as it was before the last modifying command
+    Undo {},
+
+    /// Check that two or more masters are compatible for interpolation (does not use --ufo-path)
+    CheckCompat {
+        /// The masters to compare
+        #[arg(required = true, num_args = 2..)]
+        masters: Vec<PathBuf>,
+    },
+
+    /// Copy every group from one master to others, remapping kerning of renamed groups (does not use --ufo-path)
+    SyncGroups {
+        /// The master whose groups are copied
+        #[arg(long)]
+        from: PathBuf,
+
+        /// The masters to update
+        #[arg(long, required = true, num_args = 1..)]
+        to: Vec<PathBuf>,
+
+        /// Overwrite targets whose groups have diverged from the source
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Generate an instance by interpolating between masters (does not use --ufo-path)
+    Interpolate {
+        /// The masters, spaced evenly from factor 0 (first) to 1 (last)
+        #[arg(long, required = true, num_args = 2..)]
+        masters: Vec<PathBuf>,
+
+        /// Position of the instance between the masters
+        #[arg(long, allow_hyphen_values = true)]
+        factor: f64,
+
+        /// Where to write the instance UFO
+        #[arg(long)]
+        out: PathBuf,
+    },
+
+    /// Check that every source UFO and layer of the designspace exists
+    CheckDesignspace {},
+
+    /// Write the instance UFOs defined in the designspace
+    GenerateInstances {},
+
+    /// Write a new designspace describing existing masters (does not use --ufo-path)
+    CreateDesignspace {
+        /// Where to write the designspace
+        #[arg(long)]
+        out: PathBuf,
+
+        /// An axis as tag:minimum:default:maximum (repeatable)
+        #[arg(long = "axis", required = true)]
+        axes: Vec<String>,
+
+        /// A source as path:tag=value[,tag=value...] (repeatable)
+        #[arg(long = "source", required = true)]
+        sources: Vec<String>,
+    },
+
+    /// Look up the effective kerning between two glyphs
+    KernLookup {
+        /// First glyph of the pair
+        #[arg(long)]
+        first: String,
+
+        /// Second glyph of the pair
         #[arg(long)]
-        value: i32,
+        second: String,
     },
 }
 
+impl Commands {
+    /// The file the command writes with --output, if any
+    fn output(&self) -> Option<&Path> {
+        match self {
+            Commands::FlattenKerning { output, .. }
+            | Commands::ExportFea { output, .. }
+            | Commands::ExportKerning { output } => output.as_deref(),
+            _ => None,
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
     
     // Display the ASCII art and help message if no arguments are provided
-    if cli.ufo_path.is_none() && cli.command.is_none() {
-        println!("    .     *     .           .     ");
-        println!("   .-----.                        ");
-        println!(" _/___@_@_\\_              .      ");
-        println!("(___________)      *              ");
-        println!("                                  ");
+    if cli.ufo_path.is_empty() && cli.designspace.is_empty() && cli.command.is_none() {
+        commands::print_banner();
         println!("Lil' UFO - UFO Font File Tool v{}", env!("CARGO_PKG_VERSION"));
         println!("Usage: lilufo --ufo-path <path-to-ufo-file> [COMMAND]");
+        println!("       lilufo --designspace <path-to-designspace> [COMMAND]");
         println!("For more information about available options, run: lilufo --help");
         return Ok(());
     }
     
-    // If we have a command, we need a UFO path
-    if cli.command.is_some() {
-        let ufo_path = cli.ufo_path.ok_or_else(|| anyhow::anyhow!("UFO path is required when using commands"))?;
-        
-        // Load the UFO file for commands that need it
-        let font = Font::load(&ufo_path)?;
-        
-        match &cli.command {
-            Some(Commands::BasicInfo {}) => {
-                commands::execute_basic_info(&font);
-            }
-            Some(Commands::RoundToEven {}) => {
-                commands::execute_round_to_even(&ufo_path)?;
-            }
-            Some(Commands::ShowKerningGroups {}) => {
-                commands::execute_show_kerning_groups(&ufo_path)?;
-            }
-            Some(Commands::ShowKerning {}) => {
-                commands::execute_show_kerning(&ufo_path)?;
-            }
-            Some(Commands::AddKerningGroup { name, side, members }) => {
-                // Split comma-separated members into a vector
-                let members_vec: Vec<String> = members.split(',')
-                    .map(|s| s.trim().to_string())
-                    .collect();
-                
-                commands::execute_add_kerning_group(&ufo_path, name, side, &members_vec)?;
+    // Commands that work on several UFOs given as their own arguments
+    match &cli.command {
+        Some(Commands::CheckCompat { masters }) => return commands::execute_check_compat(masters, cli.format),
+        Some(Commands::SyncGroups { from, to, force }) => {
+            return commands::execute_sync_groups(from, to, *force, cli.dry_run);
+        }
+        Some(Commands::Interpolate { masters, factor, out }) => {
+            return commands::execute_interpolate(masters, *factor, out, cli.dry_run);
+        }
+        Some(Commands::CreateDesignspace { out, axes, sources }) => {
+            let axes = axes.iter().map(|axis| axis.parse()).collect::<Result<Vec<_>>>()?;
+            let sources = sources.iter().map(|source| source.parse()).collect::<Result<Vec<_>>>()?;
+            return commands::execute_create_designspace(out, &axes, &sources, cli.dry_run);
+        }
+        Some(Commands::CheckDesignspace {}) => {
+            for designspace in designspace_paths(&cli)? {
+                commands::execute_check_designspace(designspace, cli.format)?;
             }
-            Some(Commands::EditKerningGroup { name, side, members, append }) => {
-                // Split comma-separated members into a vector
-                let members_vec: Vec<String> = members.split(',')
-                    .map(|s| s.trim().to_string())
-                    .collect();
-                
-                commands::execute_edit_kerning_group(&ufo_path, name, side, &members_vec, *append)?;
+            return Ok(());
+        }
+        Some(Commands::GenerateInstances {}) => {
+            for designspace in designspace_paths(&cli)? {
+                commands::execute_generate_instances(designspace, cli.dry_run)?;
             }
-            Some(Commands::AddKerningPair { first, second, value }) => {
-                commands::execute_add_kerning_pair(&ufo_path, first, second, *value)?;
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // A process started by a batch works on its one font
+    if let Some(ufo_path) = &cli.batch_font {
+        return run(&cli, ufo_path);
+    }
+
+    let targets = lilufo::batch::expand_targets(&cli.ufo_path, &cli.designspace)?;
+
+    // If we have a command, we need a UFO path or a designspace
+    if cli.command.is_some() {
+        match targets.as_slice() {
+            [] => return Err(anyhow::anyhow!("UFO path is required when using commands")),
+            [ufo_path] => run(&cli, ufo_path)?,
+            _ => {
+                if let Some(output) = cli.command.as_ref().and_then(Commands::output) {
+                    lilufo::batch::check_output_paths(output, &targets)?;
+                }
+                commands::execute_batch(&targets)?
             }
-            None => unreachable!(), // We already checked this above
         }
-    } else if let Some(ufo_path) = &cli.ufo_path {
-        // If we have a UFO path but no command, just load the font and show a message
-        let _font = Font::load(ufo_path)?;
+    } else if !targets.is_empty() {
+        // If we have a UFO path but no command, just load the fonts and show a message
+        for ufo_path in &targets {
+            let _font = Font::load(ufo_path)?;
+        }
         
         println!("UFO file loaded. Use a subcommand to perform operations.");
         println!("For more information about available commands, run: lilufo --help");
@@ -150,3 +469,181 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Runs the command on a single UFO
+fn run(cli: &Cli, ufo_path: &Path) -> Result<()> {
+    // Commands that work on the UFO files directly rather than a loaded font
+    match &cli.command {
+        Some(Commands::RoundToEven { layer, all_layers, advance, anchors, guidelines, components, safeguard }) => {
+            let layers = layer_selection(layer, *all_layers);
+            let elements = element_selection(*advance, *anchors, *guidelines, *components);
+            return commands::execute_round_to_even(ufo_path, &layers, &elements, *safeguard, cli.dry_run);
+        }
+        Some(Commands::SnapToGrid {
+            grid, offset, layer, all_layers, advance, anchors, guidelines, components, safeguard
+        }) => {
+            let grid = parse_grid(*grid, offset.as_deref())?;
+            let layers = layer_selection(layer, *all_layers);
+            let elements = element_selection(*advance, *anchors, *guidelines, *components);
+            return commands::execute_snap_to_grid(ufo_path, &layers, &elements, &grid, *safeguard, cli.dry_run);
+        }
+        Some(Commands::CheckGrid { grid, offset, layer, all_layers, advance, anchors, guidelines, components }) => {
+            let grid = parse_grid(*grid, offset.as_deref())?;
+            let layers = layer_selection(layer, *all_layers);
+            let elements = element_selection(*advance, *anchors, *guidelines, *components);
+            return commands::execute_check_grid(ufo_path, &layers, &elements, &grid, cli.format);
+        }
+        Some(Commands::CheckKerningGroups {}) => {
+            return commands::execute_check_kerning_groups(ufo_path, cli.format);
+        }
+        Some(Commands::CheckContents {}) => {
+            return commands::execute_check_contents(ufo_path, cli.format);
+        }
+        Some(Commands::Undo {}) => {
+            return commands::execute_undo(ufo_path, cli.dry_run);
+        }
+        _ => {}
+    }
+
+    // Load the UFO file for commands that need it
+    let mut font = Font::load(ufo_path)?;
+    // Kept to detect (or, with --dry-run, show) what the command changed
+    let original = font.clone();
+    
+    match &cli.command {
+        Some(Commands::BasicInfo {}) => {
+            commands::execute_basic_info(&font, cli.format)?;
+        }
+        Some(Commands::ShowKerningGroups {}) => {
+            commands::execute_show_kerning_groups(&font, cli.format)?;
+        }
+        Some(Commands::ShowKerning {}) => {
+            commands::execute_show_kerning(&font, cli.format)?;
+        }
+        Some(Commands::AddKerningGroup { name, side, members, move_members, allow_missing }) => {
+            // Split comma-separated members into a vector
+            let members_vec: Vec<String> = members.split(',')
+                .map(|s| s.trim().to_string())
+                .collect();
+            
+            let side: GroupSide = side.parse()?;
+            let policy = MemberPolicy { move_members: *move_members, allow_missing: *allow_missing };
+            commands::execute_add_kerning_group(&mut font, name, side, &members_vec, policy)?;
+        }
+        Some(Commands::EditKerningGroup { name, side, members, append, move_members, allow_missing }) => {
+            // Split comma-separated members into a vector
+            let members_vec: Vec<String> = members.split(',')
+                .map(|s| s.trim().to_string())
+                .collect();
+            
+            let side: GroupSide = side.parse()?;
+            let policy = MemberPolicy { move_members: *move_members, allow_missing: *allow_missing };
+            commands::execute_edit_kerning_group(&mut font, name, side, &members_vec, *append, policy)?;
+        }
+        Some(Commands::AddKerningPair { first, second, value, allow_missing }) => {
+            commands::execute_add_kerning_pair(&mut font, first, second, *value, *allow_missing)?;
+        }
+        Some(Commands::RemoveKerningPair { first, second }) => {
+            commands::execute_remove_kerning_pair(&mut font, first, second)?;
+        }
+        Some(Commands::RemoveKerningGroup { name, side, references }) => {
+            let side: GroupSide = side.parse()?;
+            commands::execute_remove_kerning_group(&mut font, name, side, *references)?;
+        }
+        Some(Commands::RoundKerning {}) => {
+            commands::execute_round_kerning(&mut font)?;
+        }
+        Some(Commands::FlattenKerning { write, output }) => {
+            let output = output_path(cli, output, ufo_path);
+            commands::execute_flatten_kerning(&mut font, *write, output.as_deref(), cli.format, cli.dry_run)?;
+        }
+        Some(Commands::KernLookup { first, second }) => {
+            commands::execute_kern_lookup(&font, first, second, cli.format)?;
+        }
+        Some(Commands::ExportFea { output, merge }) => {
+            let output = output_path(cli, output, ufo_path);
+            commands::execute_export_fea(&mut font, output.as_deref(), *merge, cli.dry_run)?;
+        }
+        Some(Commands::ImportKerning { from, strategy, allow_missing }) => {
+            commands::execute_import_kerning(&mut font, from, *strategy, *allow_missing)?;
+        }
+        Some(Commands::ExportKerning { output }) => {
+            let output = output_path(cli, output, ufo_path);
+            commands::execute_export_kerning(&font, output.as_deref(), cli.format, cli.dry_run)?;
+        }
+        Some(Commands::RoundToEven { .. })
+        | Some(Commands::SnapToGrid { .. })
+        | Some(Commands::CheckGrid { .. })
+        | Some(Commands::CheckKerningGroups {})
+        | Some(Commands::CheckContents {})
+        | Some(Commands::Undo {})
+        | Some(Commands::CheckCompat { .. })
+        | Some(Commands::SyncGroups { .. })
+        | Some(Commands::Interpolate { .. })
+        | Some(Commands::CheckDesignspace {})
+        | Some(Commands::GenerateInstances {})
+        | Some(Commands::CreateDesignspace { .. }) => {
+            unreachable!() // Handled before loading the font
+        }
+        None => unreachable!(), // We already checked this above
+    }
+
+    // Mutating commands only change the font in memory; save it here
+    if font != original {
+        if cli.dry_run {
+            commands::print_font_diff(&lilufo::diff::diff_fonts(&original, &font));
+            println!("Dry run: {} was not modified", ufo_path.display());
+        } else {
+            lilufo::backup::replace_ufo(ufo_path, |staging| Ok(font.save(staging)?))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The --output path for one UFO, which is made distinct per font in a batch
+fn output_path(cli: &Cli, output: &Option<PathBuf>, ufo_path: &Path) -> Option<PathBuf> {
+    match output {
+        Some(output) if cli.batch_font.is_some() => Some(lilufo::batch::output_path(output, ufo_path)),
+        _ => output.clone(),
+    }
+}
+
+/// The --designspace paths, for commands that need at least one
+fn designspace_paths(cli: &Cli) -> Result<&[PathBuf]> {
+    if cli.designspace.is_empty() {
+        return Err(anyhow::anyhow!("This command requires --designspace"));
+    }
+    Ok(&cli.designspace)
+}
+
+/// Accepts one of `values` and converts it with the library's `FromStr`
+fn choice<T>(values: &'static [&'static str]) -> impl TypedValueParser<Value = T>
+where
+    T: FromStr<Err = anyhow::Error> + Clone + Send + Sync + 'static,
+{
+    PossibleValuesParser::new(values.iter().copied()).try_map(|value| value.parse::<T>())
+}
+
+/// Turns the --layer/--all-layers flags into a layer selection
+fn layer_selection(layer: &Option<String>, all_layers: bool) -> LayerSelection {
+    match layer {
+        Some(name) => LayerSelection::Named(name.clone()),
+        None if all_layers => LayerSelection::All,
+        None => LayerSelection::Default,
+    }
+}
+
+/// Which elements the --advance/--anchors/--guidelines/--components flags select
+fn element_selection(advance: bool, anchors: bool, guidelines: bool, components: bool) -> ElementSelection {
+    ElementSelection { advance, anchors, guidelines, components }
+}
+
+/// Builds a grid from the --grid and --offset options
+fn parse_grid(size: f64, offset: Option<&str>) -> Result<Grid> {
+    let (x_offset, y_offset) = match offset {
+        Some(offset) => lilufo::grid::parse_offset(offset)?,
+        None => (0.0, 0.0),
+    };
+    Grid::new(size, x_offset, y_offset)
+}
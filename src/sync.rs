@@ -0,0 +1,257 @@
+//! Keeping groups identical across masters
+//!
+//! Usage:
+//! ```bash
+//! # Copy Regular's groups into the other masters
+//! lilufo sync-groups --from Regular.ufo --to Light.ufo Bold.ufo
+//!
+//! # Overwrite masters whose groups have diverged
+//! lilufo sync-groups --from Regular.ufo --to Light.ufo Bold.ufo --force
+//! ```
+//!
+//! Every group of the source, kerning or not, replaces the target's. A
+//! target group that is missing from the source but has the same members
+//! as a source group missing from the target counts as renamed, and the
+//! target's kerning is rewritten to use the new name. Anything else the
+//! target has changed on its own is a [`SyncConflict`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use anyhow::Result;
+use norad::{Font, Groups, Kerning, Name};
+use crate::kerning::{display_name, GroupSide, KerningPair};
+
+/// A group whose name differs between the source and the target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// A way in which the target's groups or kerning diverged from the source
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncConflict {
+    /// The group exists in both but with different members
+    Members { group: String, source: Vec<String>, target: Vec<String> },
+    /// The group only exists in the target and is not a rename; kerning
+    /// pairs that reference it are dropped
+    Extra { group: String, members: Vec<String>, pairs: usize },
+    /// Renaming a group would replace a pair the target already kerns
+    Pair { first: String, second: String },
+}
+
+impl fmt::Display for SyncConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncConflict::Members { group, source, target } => write!(
+                f,
+                "{} has different members (source: {}; target: {})",
+                display_name(group),
+                source.join(", "),
+                target.join(", ")
+            ),
+            SyncConflict::Extra { group, members, pairs } => {
+                write!(f, "{} ({}) is not in the source", display_name(group), members.join(", "))?;
+                if *pairs > 0 {
+                    write!(f, "; {} kerning pair(s) using it would be dropped", pairs)?;
+                }
+                Ok(())
+            }
+            SyncConflict::Pair { first, second } => write!(
+                f,
+                "kerning pair {} {} exists under both the old and the new group name",
+                display_name(first),
+                display_name(second)
+            ),
+        }
+    }
+}
+
+/// What [`sync_groups`] changed in a target
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupSync {
+    /// Source groups the target did not have
+    pub added: Vec<String>,
+    /// Groups whose members were replaced or reordered
+    pub updated: Vec<String>,
+    pub renamed: Vec<GroupRename>,
+    /// Target groups that are not in the source
+    pub removed: Vec<String>,
+    /// Kerning pairs moved to a renamed group, as (before, after)
+    pub remapped_pairs: Vec<(KerningPair, KerningPair)>,
+    /// Kerning pairs that referenced a removed group
+    pub dropped_pairs: Vec<KerningPair>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+impl GroupSync {
+    /// Whether the target's groups already matched the source
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.renamed.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn members(groups: &Groups, name: &str) -> Vec<String> {
+    groups.get(name).map(|members| members.iter().map(|m| m.to_string()).collect()).unwrap_or_default()
+}
+
+fn member_set(groups: &Groups, name: &str) -> BTreeSet<String> {
+    members(groups, name).into_iter().collect()
+}
+
+/// Only groups of the same kind (left, right or non-kerning) can be renames
+fn same_kind(a: &str, b: &str) -> bool {
+    GroupSide::split_full_name(a).map(|(side, _)| side) == GroupSide::split_full_name(b).map(|(side, _)| side)
+}
+
+/// Replaces the target's groups with the source's, remapping the target's
+/// kerning for renamed groups. The target is changed even if there are
+/// conflicts; callers that want to keep a diverged target should sync a copy.
+pub fn sync_groups(source: &Font, target: &mut Font) -> Result<GroupSync> {
+    let mut sync = GroupSync::default();
+    let mut unmatched: Vec<&Name> = source.groups.keys().filter(|name| !target.groups.contains_key(*name)).collect();
+    let mut renames: BTreeMap<String, String> = BTreeMap::new();
+
+    for (name, target_members) in &target.groups {
+        if let Some(source_members) = source.groups.get(name) {
+            if source_members != target_members {
+                sync.updated.push(name.to_string());
+            }
+            if member_set(&source.groups, name) != member_set(&target.groups, name) {
+                sync.conflicts.push(SyncConflict::Members {
+                    group: name.to_string(),
+                    source: members(&source.groups, name),
+                    target: members(&target.groups, name),
+                });
+            }
+            continue;
+        }
+
+        let target_set = member_set(&target.groups, name);
+        let renamed = unmatched
+            .iter()
+            .position(|candidate| same_kind(candidate, name) && member_set(&source.groups, candidate) == target_set);
+        match renamed {
+            Some(index) => {
+                let to = unmatched.remove(index).to_string();
+                renames.insert(name.to_string(), to.clone());
+                sync.renamed.push(GroupRename { from: name.to_string(), to });
+            }
+            None => sync.removed.push(name.to_string()),
+        }
+    }
+    sync.added = unmatched.iter().map(|name| name.to_string()).collect();
+
+    // Pairs that keep their keys go in first so a remapped pair never replaces one
+    let mut kerning = Kerning::new();
+    let mut remapped = Vec::new();
+    for (first, seconds) in &target.kerning {
+        for (second, value) in seconds {
+            let pair = KerningPair { first: first.to_string(), second: second.to_string(), value: *value };
+            if sync.removed.contains(&pair.first) || sync.removed.contains(&pair.second) {
+                sync.dropped_pairs.push(pair);
+                continue;
+            }
+            let new_first = renames.get(&pair.first).cloned().unwrap_or_else(|| pair.first.clone());
+            let new_second = renames.get(&pair.second).cloned().unwrap_or_else(|| pair.second.clone());
+            if new_first == pair.first && new_second == pair.second {
+                kerning.entry(first.clone()).or_default().insert(second.clone(), *value);
+            } else {
+                remapped.push((pair, KerningPair { first: new_first, second: new_second, value: *value }));
+            }
+        }
+    }
+    for (before, after) in remapped {
+        let seconds = kerning.entry(Name::new(&after.first)?).or_default();
+        if seconds.contains_key(after.second.as_str()) {
+            sync.conflicts.push(SyncConflict::Pair { first: after.first.clone(), second: after.second.clone() });
+        } else {
+            seconds.insert(Name::new(&after.second)?, after.value);
+            sync.remapped_pairs.push((before, after));
+        }
+    }
+
+    for group in &sync.removed {
+        let pairs = sync.dropped_pairs.iter().filter(|pair| pair.first == *group || pair.second == *group).count();
+        sync.conflicts.push(SyncConflict::Extra { group: group.clone(), members: members(&target.groups, group), pairs });
+    }
+
+    target.groups = source.groups.clone();
+    target.kerning = kerning;
+    Ok(sync)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kerning::test_support::font;
+
+    fn pairs(font: &Font) -> Vec<(String, String, f64)> {
+        font.kerning
+            .iter()
+            .flat_map(|(first, seconds)| seconds.iter().map(move |(second, value)| (first.to_string(), second.to_string(), *value)))
+            .collect()
+    }
+
+    #[test]
+    fn renamed_groups_keep_their_kerning() {
+        let source = font(&[], &[("public.kern1.O", &["O", "Q"]), ("public.kern2.A", &["A"])], &[]);
+        let mut target = font(
+            &[],
+            &[("public.kern1.round", &["Q", "O"]), ("public.kern2.A", &["A"])],
+            &[("public.kern1.round", "public.kern2.A", -20.0), ("T", "public.kern2.A", -40.0)],
+        );
+        let sync = sync_groups(&source, &mut target).unwrap();
+
+        assert_eq!(sync.renamed, [GroupRename { from: "public.kern1.round".into(), to: "public.kern1.O".into() }]);
+        assert!(sync.conflicts.is_empty());
+        assert_eq!(sync.remapped_pairs.len(), 1);
+        assert_eq!(target.groups, source.groups);
+        assert_eq!(
+            pairs(&target),
+            [("T".into(), "public.kern2.A".into(), -40.0), ("public.kern1.O".into(), "public.kern2.A".into(), -20.0)]
+        );
+    }
+
+    #[test]
+    fn renames_only_match_groups_of_the_same_side() {
+        let source = font(&[], &[("public.kern2.O", &["O"])], &[]);
+        let mut target = font(&[], &[("public.kern1.O", &["O"])], &[("public.kern1.O", "A", -10.0)]);
+        let sync = sync_groups(&source, &mut target).unwrap();
+
+        assert!(sync.renamed.is_empty());
+        assert_eq!(sync.added, ["public.kern2.O"]);
+        assert_eq!(sync.removed, ["public.kern1.O"]);
+        assert_eq!(sync.conflicts, [SyncConflict::Extra { group: "public.kern1.O".into(), members: vec!["O".into()], pairs: 1 }]);
+        assert_eq!(sync.dropped_pairs.len(), 1);
+        assert!(target.kerning.is_empty());
+    }
+
+    #[test]
+    fn reordered_members_are_an_update_but_not_a_conflict() {
+        let source = font(&[], &[("public.kern1.O", &["O", "Q"]), ("public.kern2.A", &["A"])], &[]);
+        let mut target = font(&[], &[("public.kern1.O", &["Q", "O"]), ("public.kern2.A", &["A", "Aacute"])], &[]);
+        let sync = sync_groups(&source, &mut target).unwrap();
+
+        assert_eq!(sync.updated, ["public.kern1.O", "public.kern2.A"]);
+        assert_eq!(
+            sync.conflicts,
+            [SyncConflict::Members { group: "public.kern2.A".into(), source: vec!["A".into()], target: vec!["A".into(), "Aacute".into()] }]
+        );
+    }
+
+    #[test]
+    fn a_remapped_pair_never_replaces_an_existing_one() {
+        let source = font(&[], &[("public.kern1.O", &["O", "Q"])], &[]);
+        let mut target = font(
+            &[],
+            &[("public.kern1.round", &["O", "Q"])],
+            &[("public.kern1.round", "A", -20.0), ("public.kern1.O", "A", -35.0)],
+        );
+        let sync = sync_groups(&source, &mut target).unwrap();
+
+        assert_eq!(sync.conflicts, [SyncConflict::Pair { first: "public.kern1.O".into(), second: "A".into() }]);
+        assert!(sync.remapped_pairs.is_empty());
+        assert_eq!(pairs(&target), [("public.kern1.O".into(), "A".into(), -35.0)]);
+    }
+}
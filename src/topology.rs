@@ -0,0 +1,279 @@
+//! Contour topology checks for grid snapping
+//!
+//! Snapping to a coarse grid can collapse neighbouring on-curve points onto
+//! each other, shrink whole segments to nothing, or turn a small contour
+//! inside out. These checks compare a contour before and after snapping and
+//! only report degeneracies that snapping introduced.
+
+use std::fmt;
+use std::str::FromStr;
+use anyhow::Result;
+use crate::grid::{Axis, Grid};
+
+/// A point of a contour as read from a `.glif` file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContourPoint {
+    pub x: f64,
+    pub y: f64,
+    pub on_curve: bool,
+}
+
+impl ContourPoint {
+    fn same_position(&self, other: &ContourPoint) -> bool {
+        self.x == other.x && self.y == other.y
+    }
+}
+
+/// A contour's points, and whether it is closed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    pub points: Vec<ContourPoint>,
+    /// Open contours start with a `move` point
+    pub closed: bool,
+}
+
+/// What to do with a glyph whose contours snapping would degrade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Safeguard {
+    /// Move the offending points to the nearest grid position that keeps
+    /// the contour intact, skipping the glyph if none is found
+    Nudge,
+    /// Leave the glyph untouched and report it
+    Skip,
+}
+
+impl FromStr for Safeguard {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "nudge" => Ok(Safeguard::Nudge),
+            "skip" => Ok(Safeguard::Skip),
+            _ => Err(anyhow::anyhow!("safeguard must be either 'nudge' or 'skip'")),
+        }
+    }
+}
+
+/// A kind of degenerate result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// Two adjacent on-curve points now share a position
+    CoincidentPoints { first: usize, second: usize },
+    /// A segment, including any off-curve points, shrank to a single position
+    ZeroLengthSegment { first: usize, second: usize },
+    /// The contour now runs the other way round
+    FlippedDirection,
+    /// The contour no longer encloses any area
+    CollapsedContour,
+}
+
+/// A degenerate result found in one contour of a glyph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopologyIssue {
+    /// Index of the contour within the glyph's outline
+    pub contour: usize,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for TopologyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            IssueKind::CoincidentPoints { first, second } => {
+                write!(f, "contour {}: points {} and {} coincide", self.contour, first, second)
+            }
+            IssueKind::ZeroLengthSegment { first, second } => {
+                write!(f, "contour {}: segment from point {} to {} has zero length", self.contour, first, second)
+            }
+            IssueKind::FlippedDirection => write!(f, "contour {}: direction flipped", self.contour),
+            IssueKind::CollapsedContour => write!(f, "contour {}: collapsed to zero area", self.contour),
+        }
+    }
+}
+
+/// Lists the degeneracies present in `snapped` but not in `original`
+pub fn contour_issues(index: usize, original: &Contour, snapped: &[ContourPoint]) -> Vec<TopologyIssue> {
+    let mut issues = Vec::new();
+    let issue = |kind| TopologyIssue { contour: index, kind };
+
+    let on_curve: Vec<usize> = (0..snapped.len()).filter(|&i| snapped[i].on_curve).collect();
+    let segment_count = if original.closed { on_curve.len() } else { on_curve.len().saturating_sub(1) };
+    if on_curve.len() > 1 {
+        for s in 0..segment_count {
+            let (first, second) = (on_curve[s], on_curve[(s + 1) % on_curve.len()]);
+            if !snapped[first].same_position(&snapped[second])
+                || original.points[first].same_position(&original.points[second])
+            {
+                continue;
+            }
+            if segment_indices(first, second, snapped.len()).all(|i| snapped[i].same_position(&snapped[first])) {
+                issues.push(issue(IssueKind::ZeroLengthSegment { first, second }));
+            } else {
+                issues.push(issue(IssueKind::CoincidentPoints { first, second }));
+            }
+        }
+    }
+
+    if original.closed {
+        let before = signed_area(&original.points);
+        let after = signed_area(snapped);
+        if before != 0.0 {
+            if after == 0.0 {
+                issues.push(issue(IssueKind::CollapsedContour));
+            } else if before.signum() != after.signum() {
+                issues.push(issue(IssueKind::FlippedDirection));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Indices from `first` to `second` inclusive, wrapping around the contour
+fn segment_indices(first: usize, second: usize, len: usize) -> impl Iterator<Item = usize> {
+    let steps = (second + len - first) % len;
+    (0..=steps).map(move |step| (first + step) % len)
+}
+
+/// Twice the signed area of the control polygon
+fn signed_area(points: &[ContourPoint]) -> f64 {
+    (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum()
+}
+
+/// Moves points of `snapped` to neighbouring grid positions until the
+/// contour has no issues left. Returns `false` if that is not possible.
+pub fn nudge_contour(index: usize, original: &Contour, snapped: &mut [ContourPoint], grid: &Grid) -> bool {
+    // Each successful nudge removes at least one issue, but a nudge can also
+    // shift a problem to a neighbour, so allow a few passes per point
+    for _ in 0..snapped.len() * 4 {
+        let issues = contour_issues(index, original, snapped);
+        let Some(first_issue) = issues.first() else {
+            return true;
+        };
+
+        let candidates: Vec<usize> = match first_issue.kind {
+            IssueKind::CoincidentPoints { first, second } | IssueKind::ZeroLengthSegment { first, second } => {
+                vec![first, second]
+            }
+            IssueKind::FlippedDirection | IssueKind::CollapsedContour => (0..snapped.len()).collect(),
+        };
+
+        let mut best: Option<(usize, ContourPoint, usize, f64)> = None;
+        for &point in &candidates {
+            for position in neighbours(snapped[point], grid) {
+                let previous = std::mem::replace(&mut snapped[point], position);
+                let remaining = contour_issues(index, original, snapped).len();
+                snapped[point] = previous;
+
+                let distance = (position.x - original.points[point].x).hypot(position.y - original.points[point].y);
+                let better = match best {
+                    None => true,
+                    Some((_, _, best_remaining, best_distance)) => {
+                        (remaining, distance) < (best_remaining, best_distance)
+                    }
+                };
+                if remaining < issues.len() && better {
+                    best = Some((point, position, remaining, distance));
+                }
+            }
+        }
+
+        match best {
+            Some((point, position, _, _)) => snapped[point] = position,
+            None => return false,
+        }
+    }
+    contour_issues(index, original, snapped).is_empty()
+}
+
+/// The eight grid positions surrounding a snapped point
+fn neighbours(point: ContourPoint, grid: &Grid) -> impl Iterator<Item = ContourPoint> + '_ {
+    [(-1.0, -1.0), (0.0, -1.0), (1.0, -1.0), (-1.0, 0.0), (1.0, 0.0), (-1.0, 1.0), (0.0, 1.0), (1.0, 1.0)]
+        .into_iter()
+        .map(move |(dx, dy)| ContourPoint {
+            x: grid.snap(point.x + dx * grid.size, Axis::X),
+            y: grid.snap(point.y + dy * grid.size, Axis::Y),
+            on_curve: point.on_curve,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contour(points: &[(f64, f64)]) -> Contour {
+        Contour { points: points.iter().map(|&(x, y)| ContourPoint { x, y, on_curve: true }).collect(), closed: true }
+    }
+
+    fn snap(contour: &Contour, grid: &Grid) -> Vec<ContourPoint> {
+        contour
+            .points
+            .iter()
+            .map(|p| ContourPoint { x: grid.snap(p.x, Axis::X), y: grid.snap(p.y, Axis::Y), ..*p })
+            .collect()
+    }
+
+    fn kinds(issues: &[TopologyIssue]) -> Vec<IssueKind> {
+        issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn reports_points_that_snapping_merges() {
+        let thin = contour(&[(0.0, 0.0), (100.0, 0.0), (100.0, 4.0), (0.0, 4.0)]);
+        let snapped = snap(&thin, &Grid::new(10.0, 0.0, 0.0).unwrap());
+        assert_eq!(
+            kinds(&contour_issues(3, &thin, &snapped)),
+            [
+                IssueKind::ZeroLengthSegment { first: 1, second: 2 },
+                IssueKind::ZeroLengthSegment { first: 3, second: 0 },
+                IssueKind::CollapsedContour,
+            ]
+        );
+        assert_eq!(contour_issues(3, &thin, &snapped)[0].to_string(), "contour 3: segment from point 1 to 2 has zero length");
+    }
+
+    #[test]
+    fn ignores_degeneracies_that_were_already_there() {
+        let square = contour(&[(0.0, 0.0), (0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        let snapped = snap(&square, &Grid::new(10.0, 0.0, 0.0).unwrap());
+        assert!(contour_issues(0, &square, &snapped).is_empty());
+    }
+
+    #[test]
+    fn nudging_keeps_a_small_contour_intact() {
+        let grid = Grid::new(10.0, 0.0, 0.0).unwrap();
+        let thin = contour(&[(0.0, 0.0), (100.0, 0.0), (100.0, 4.0), (0.0, 4.0)]);
+        let mut snapped = snap(&thin, &grid);
+        assert!(nudge_contour(0, &thin, &mut snapped, &grid));
+        assert!(contour_issues(0, &thin, &snapped).is_empty());
+        assert!(snapped.iter().all(|p| grid.snap(p.x, Axis::X) == p.x && grid.snap(p.y, Axis::Y) == p.y));
+    }
+
+    #[test]
+    fn nudging_separates_an_open_dot() {
+        let grid = Grid::new(100.0, 0.0, 0.0).unwrap();
+        let dot = Contour { closed: false, ..contour(&[(0.0, 0.0), (1.0, 1.0)]) };
+        let mut snapped = snap(&dot, &grid);
+        assert_eq!(kinds(&contour_issues(0, &dot, &snapped)), [IssueKind::ZeroLengthSegment { first: 0, second: 1 }]);
+        assert!(nudge_contour(0, &dot, &mut snapped, &grid));
+        assert_ne!(snapped[0], snapped[1]);
+    }
+
+    #[test]
+    fn nudging_gives_up_when_one_point_cannot_help() {
+        // A teardrop with a single on-curve point: moving any one point
+        // leaves two of the three on top of each other, so no area returns
+        let grid = Grid::new(100.0, 0.0, 0.0).unwrap();
+        let mut teardrop = contour(&[(40.0, 40.0), (40.0, 0.0), (0.0, 0.0)]);
+        teardrop.points[0].on_curve = false;
+        teardrop.points[1].on_curve = false;
+        let mut snapped = snap(&teardrop, &grid);
+        assert_eq!(kinds(&contour_issues(0, &teardrop, &snapped)), [IssueKind::CollapsedContour]);
+        assert!(!nudge_contour(0, &teardrop, &mut snapped, &grid));
+        assert_eq!(snapped, snap(&teardrop, &grid));
+    }
+}